@@ -15,25 +15,43 @@ use afbv4::prelude::*;
 use ocpp::prelude::*;
 use typesv4::prelude::*;
 
+// csms Reset action once transactions are closed, receives 'hard' (reboot) or 'soft' (binder restart)
+#[derive(Clone, Copy)]
+pub enum ResetHook {
+    // api, verb
+    Verb(&'static str, &'static str),
+    // local shell command
+    Command(&'static str),
+}
+
 pub struct BindingConfig {
     pub chmgr_api: &'static str,
+    pub unlock_verb: &'static str,
     pub engy_api: &'static str,
-    pub station: &'static str,
+    pub identity: &'static OcppIdentity,
     pub mgr: &'static ManagerHandle,
     pub cid: u32,
+    pub version: OcppVersion,
+    pub chargepoint: &'static dyn OcppChargePoint,
+    pub soft_error: bool,
+    pub store: &'static OcppConfigStore,
+    pub auth: &'static OcppAuth,
+    pub reset_hook: Option<ResetHook>,
 }
 
 pub struct ApiUserData {
     pub mgr: &'static ManagerHandle,
-    pub station: &'static str,
+    pub identity: &'static OcppIdentity,
     pub evt: &'static AfbEvent,
-    pub tic: u32,
+    pub store: &'static OcppConfigStore,
+    pub auth: &'static OcppAuth,
+    pub chargepoint: &'static dyn OcppChargePoint,
 }
 
 impl AfbApiControls for ApiUserData {
     // the API is created and ready. At this level user may subcall api(s) declare as dependencies
     fn start(&mut self, api: &AfbApi) -> Result<(), AfbError> {
-        ocpp_bootstrap(api, self.mgr,self.station, self.tic)?;
+        ocpp_bootstrap(api, self.mgr, self.chargepoint, self.store, self.auth, self.identity)?;
         self.evt.push (OcppMsg::Initialized);
         AfbSubCall::call_sync(
         api,
@@ -49,6 +67,47 @@ impl AfbApiControls for ApiUserData {
     }
 }
 
+fn identity_value(jident: &JsoncObj, key: &str) -> Result<Option<String>, AfbError> {
+    match jident.get::<String>(key) {
+        Ok(value) => Ok(Some(OcppIdentity::read_value(&value)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+// BootNotification identity, 'station' remains vendor default for older configs
+fn parse_identity(
+    jconf: &JsoncObj,
+    station: &str,
+    version: OcppVersion,
+) -> Result<&'static OcppIdentity, AfbError> {
+    let jident = match jconf.get::<JsoncObj>("identity") {
+        Ok(value) => value,
+        Err(_) => JsoncObj::new(),
+    };
+    let model = match version {
+        OcppVersion::V106 => "Tux-Evse OCPP-1.6",
+        OcppVersion::V201 => "Tux-Evse OCPP-2.0.1",
+    };
+
+    let mut identity = OcppIdentity::new(
+        &identity_value(&jident, "vendor")?.unwrap_or(station.to_string()),
+        &identity_value(&jident, "model")?.unwrap_or(model.to_string()),
+    );
+    identity.firmware = match identity_value(&jident, "firmware")? {
+        Some(value) => Some(value),
+        None => Some(format!("afb-ocpp-{}", env!("CARGO_PKG_VERSION"))),
+    };
+    identity.serial_number = identity_value(&jident, "serial_number")?;
+    identity.box_serial = identity_value(&jident, "box_serial")?;
+    identity.iccid = identity_value(&jident, "iccid")?;
+    identity.imsi = identity_value(&jident, "imsi")?;
+    identity.meter_type = identity_value(&jident, "meter_type")?;
+    identity.meter_serial = identity_value(&jident, "meter_serial")?;
+    identity.check()?;
+
+    Ok(Box::leak(Box::new(identity)))
+}
+
 // Binding init callback started at binding load time before any API exist
 // -----------------------------------------
 pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi, AfbError> {
@@ -61,10 +120,50 @@ pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi
     let tic = jconf.default::<u32>("tic",0)?;
     let station = jconf.default::<&'static str>("station","tux-evse")?;
     let chmgr_api = jconf.default::<&'static str>("chmgr_api", "")?;
+    // UnlockConnector verb on chmgr_api, called with connector id
+    let unlock_verb = jconf.default::<&'static str>("unlock_verb", "unlock")?;
     let engy_api = jconf.default::<&'static str>("engy_api", "")?;
+    let version = jconf
+        .default::<&'static str>("version", "1.6")?
+        .parse::<OcppVersion>()?;
+    // some csms cut connection on CALLERROR, when set backend errors return an empty result
+    let soft_error = jconf.default::<bool>("soft_error", false)?;
+    // csms quirk, when false connector 0 is never reported and csms requests targeting it are rejected
+    let connector_zero = jconf.default::<bool>("connector_zero", true)?;
+
+    // 'reset_verb' is called on chmgr_api, 'reset_cmd' runs a local command, none rejects Hard reset
+    let reset_hook = if let Ok(verb) = jconf.get::<String>("reset_verb") {
+        if chmgr_api.is_empty() {
+            return afb_error!("ocpp-reset-config", "reset_verb:{} requires chmgr_api", verb);
+        }
+        Some(ResetHook::Verb(chmgr_api, to_static_str(verb)))
+    } else if let Ok(command) = jconf.get::<String>("reset_cmd") {
+        Some(ResetHook::Command(to_static_str(command)))
+    } else {
+        None
+    };
+
+    // 'free_vend' true accepts every tag, a string also replaces presented tag in StartTransaction
+    let free_vend = match jconf.get::<bool>("free_vend") {
+        Ok(true) => OcppFreeVend::AnyTag,
+        Ok(false) => OcppFreeVend::Disabled,
+        Err(_) => match jconf.get::<String>("free_vend") {
+            Ok(tag) if tag.chars().count() > 20 => {
+                return afb_error!("ocpp-free-vend-config", "free_vend:{} longer than 20 characters", tag)
+            }
+            Ok(tag) => OcppFreeVend::IdTag(to_static_str(tag)),
+            Err(_) => OcppFreeVend::Disabled,
+        },
+    };
+
+    // values may be read from local files '@/path' (first line) or '@/path#KEY' (e.g. @/etc/os-release#VERSION_ID)
+    let identity = parse_identity(&jconf, station, version)?;
 
     // register data converter
-    v106::register_datatype()?;
+    match version {
+        OcppVersion::V106 => v106::register_datatype()?,
+        OcppVersion::V201 => v201::register_datatype()?,
+    }
     chmgr_registers()?;
     ocpp_registers()?;
     engy_registers()?;
@@ -72,25 +171,71 @@ pub fn binding_init(rootv4: AfbApiV4, jconf: JsoncObj) -> Result<&'static AfbApi
     // create occp manager
     let event = AfbEvent::new("msg");
     let mgr = ManagerHandle::new(cid, event);
+    mgr.set_connector_zero(connector_zero);
+
+    // multi-socket station, 'cid' remains default connector and keeps 'msg' event
+    let mut events = Vec::new();
+    if let Ok(jconnectors) = jconf.get::<JsoncObj>("connectors") {
+        for idx in 0..jconnectors.count()? {
+            let connector = jconnectors.index::<u32>(idx)?;
+            if connector == cid {
+                continue;
+            }
+            let event = AfbEvent::new(to_static_str(format!("msg-{}", connector)));
+            mgr.add_connector(connector, event)?;
+            events.push(event);
+        }
+    }
+    let chargepoint = new_charge_point(version);
+
+    // configuration keys, 'tic' (ms) is HeartbeatInterval initial value, csms changes persist in 'config_path'
+    let config_path = match jconf.get::<String>("config_path") {
+        Ok(value) => Some(to_static_str(value)),
+        Err(_) => None,
+    };
+    let store = OcppConfigStore::new(v106_core_keys((tic + 999) / 1000, mgr.get_connectors().len() as u32), config_path);
+    match store.load() {
+        Ok(count) => afb_log_msg!(Info, rootv4, "config:{:?} restored keys:{}", config_path, count),
+        Err(error) => afb_log_msg!(Warning, rootv4, "ignored persisted config {}", error.get_info()),
+    }
+    store.set_value(CFG_RESERVE_CONNECTOR_ZERO_SUPPORTED, &connector_zero.to_string())?;
+    // restore connectors availability persisted by ChangeAvailability
+    mgr.set_store(store);
+    // local authorization list persisted by SendLocalList
+    let auth = OcppAuth::new(store, free_vend);
     let config = BindingConfig {
-        station,
+        identity,
         chmgr_api,
+        unlock_verb,
         engy_api,
         mgr,
         cid,
+        version,
+        chargepoint,
+        soft_error,
+        store,
+        auth,
+        reset_hook,
     };
 
     // create backend API (OCPP upercase is impose by transport extension)
     let backend = AfbApi::new("OCPP-REC").set_info(info);
-    register_backend(backend, &config)?;
+    match version {
+        OcppVersion::V106 => register_backend(backend, &config)?,
+        OcppVersion::V201 => register_backend_v201(backend, &config)?,
+    }
 
     // create an register frontend api and register init session callback
     let frontend = AfbApi::new(api)
         .set_info(info)
         .add_event(event)
         .require_api(engy_api)
-        .set_callback(Box::new(ApiUserData { mgr, station, evt:event, tic }));
+        .set_callback(Box::new(ApiUserData { mgr, identity, evt:event, store, auth, chargepoint }));
+    for event in events {
+        frontend.add_event(event);
+    }
 
+    // frontend verbs are version agnostic, messages are built by chargepoint
     register_frontend(frontend, &config)?;
 
     // if acls set apply them
@@ -18,17 +18,34 @@
 #[cfg(not(afbv4))]
 extern crate afbv4;
 
+// backend verb wrapper, action errors are returned to csms as OCPP CALLERROR
+macro_rules! backend_verb {
+    ($callback:ident, $action:ident, $ctx:ty, $version:expr) => {
+        fn $callback(rqt: &AfbRequest, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+            let ctx = ctx.get_ref::<$ctx>()?;
+            if let Err(error) = $action(rqt, args, ctx) {
+                backend_error(rqt, $version, ctx.soft_error, &error);
+            }
+            Ok(())
+        }
+    };
+}
+
 #[path = "verbs-frontend.rs"]
 mod frontend;
 
 #[path = "verbs-backend.rs"]
 mod backend;
 
+#[path = "verbs-backend-v201.rs"]
+mod backend_v201;
+
 #[path = "binding.rs"]
 mod binding;
 
 pub(crate) mod prelude {
     pub(crate) use crate::backend::*;
     pub use crate::frontend::*;
+    pub(crate) use crate::backend_v201::*;
     pub use crate::binding::*;
 }
\ No newline at end of file
@@ -0,0 +1,391 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Reference: OCPP-2.0.1 part2 specification
+ */
+
+// OCPP-2.0.1 backend requests, share ManagerHandle with OCPP-1.6 and remap its statuses
+
+use crate::prelude::*;
+use afbv4::prelude::*;
+use ocpp::prelude::*;
+use std::time::Duration;
+use typesv4::prelude::*;
+
+// csms initiated actions without implementation, answered with NotImplemented. Together with
+// registered verbs it covers every 2.0.1 csms to charging station action.
+const V201_NOT_IMPLEMENTED: [&str; 34] = [
+    "CertificateSigned",
+    "ClearCache",
+    "ClearChargingProfile",
+    "ClearDisplayMessage",
+    "ClearVariableMonitoring",
+    "CostUpdated",
+    "CustomerInformation",
+    "DataTransfer",
+    "DeleteCertificate",
+    "GetBaseReport",
+    "GetChargingProfiles",
+    "GetCompositeSchedule",
+    "GetDisplayMessages",
+    "GetInstalledCertificateIds",
+    "GetLocalListVersion",
+    "GetLog",
+    "GetMonitoringReport",
+    "GetReport",
+    "GetTransactionStatus",
+    "GetVariables",
+    "InstallCertificate",
+    "PublishFirmware",
+    "RequestStartTransaction",
+    "SendLocalList",
+    "SetDisplayMessage",
+    "SetMonitoringBase",
+    "SetMonitoringLevel",
+    "SetNetworkProfile",
+    "SetVariableMonitoring",
+    "SetVariables",
+    "TriggerMessage",
+    "UnlockConnector",
+    "UnpublishFirmware",
+    "UpdateFirmware",
+];
+
+struct CancelReservationCtx {
+    mgr: &'static ManagerHandle,
+    soft_error: bool,
+}
+backend_verb!(
+    cancel_reservation_cb,
+    cancel_reservation_action,
+    CancelReservationCtx,
+    OcppVersion::V201
+);
+fn cancel_reservation_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &CancelReservationCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v201::CancelReservationRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend cancel reservation{:?}", value);
+    let status = match ctx.mgr.reserv_cancel(value.reservation_id)? {
+        v106::CancelReservationStatus::Accepted => v201::CancelReservationStatusEnumType::Accepted,
+        v106::CancelReservationStatus::Rejected => v201::CancelReservationStatusEnumType::Rejected,
+    };
+    let response = v201::CancelReservationResponse {
+        status,
+        status_info: None,
+    };
+    rqt.reply(v201::CancelReservationKind::Response(response), 0);
+    Ok(())
+}
+
+struct ReserveNowCtx {
+    mgr: &'static ManagerHandle,
+    soft_error: bool,
+}
+backend_verb!(
+    reserve_now_cb,
+    reserve_now_action,
+    ReserveNowCtx,
+    OcppVersion::V201
+);
+fn reserve_now_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &ReserveNowCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v201::ReserveNowRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend reserve now {:?}", value);
+
+    let reservation = ReservationSession {
+        id: value.id,
+        tagid: value.id_token.id_token.clone(),
+        start: Duration::new(0, 0),
+        stop: now_to_duration(value.expiry_date_time)?,
+        status: ReservationStatus::Pending,
+    };
+
+    // reservation without evse targets the whole station
+    let cid = value.evse_id.unwrap_or(0).max(0) as u32;
+    let status = match ctx.mgr.reserv_now(cid, reservation)? {
+        v106::ReservationStatus::Accepted => v201::ReserveNowStatusEnumType::Accepted,
+        v106::ReservationStatus::Faulted => v201::ReserveNowStatusEnumType::Faulted,
+        v106::ReservationStatus::Occupied => v201::ReserveNowStatusEnumType::Occupied,
+        v106::ReservationStatus::Rejected => v201::ReserveNowStatusEnumType::Rejected,
+        v106::ReservationStatus::Unavailable => v201::ReserveNowStatusEnumType::Unavailable,
+    };
+    let response = v201::ReserveNowResponse {
+        status,
+        status_info: None,
+    };
+    rqt.reply(v201::ReserveNowKind::Response(response), 0);
+    Ok(())
+}
+
+// 2.0.1 Reset has no hard/soft kind, reset hook receives 'soft'
+struct ResetVerbCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    hook: Option<ResetHook>,
+    soft_error: bool,
+}
+backend_verb!(reset_cb, reset_action, ResetVerbCtx, OcppVersion::V201);
+fn reset_action(rqt: &AfbRequest, args: &AfbRqtData, ctx: &ResetVerbCtx) -> Result<(), AfbError> {
+    let reset = args_decode::<v201::ResetRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend reset {:?}", reset);
+    // Immediate stops running transactions as 1.6 Hard reset, OnIdle as Soft once they are ended
+    let (status, reason) = match reset.kind {
+        v201::ResetEnumType::Immediate => (v201::ResetStatusEnumType::Accepted, OcppStopReason::HardReset),
+        v201::ResetEnumType::OnIdle => {
+            if !ctx.mgr.get_transactions().is_empty() {
+                ctx.mgr.reset_schedule(OcppStopReason::SoftReset);
+                (v201::ResetStatusEnumType::Scheduled, OcppStopReason::SoftReset)
+            } else {
+                (v201::ResetStatusEnumType::Accepted, OcppStopReason::SoftReset)
+            }
+        }
+    };
+
+    let accepted = status == v201::ResetStatusEnumType::Accepted;
+    let response = v201::ResetResponse {
+        status,
+        status_info: None,
+    };
+    rqt.reply(v201::ResetKind::Response(response), 0);
+
+    // scheduled reset runs from transaction_closed, already replied, errors are only logged
+    if accepted {
+        if let Err(error) = reset_station(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, ctx.hook, reason) {
+            afb_log_msg!(Warning, rqt, "Reset failed {}", error.get_info());
+        }
+    }
+    Ok(())
+}
+
+struct SetChargingProfileCtx {
+    mgr: &'static ManagerHandle,
+    soft_error: bool,
+}
+backend_verb!(
+    set_charging_profile_cb,
+    set_charging_profile_action,
+    SetChargingProfileCtx,
+    OcppVersion::V201
+);
+fn set_charging_profile_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &SetChargingProfileCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v201::SetChargingProfileRequest>(args, 0)?;
+    let profile = &value.charging_profile;
+    let target_tid = match &profile.transaction_id {
+        Some(value) => value.parse::<i32>().unwrap_or(-1),
+        None => -1,
+    };
+
+    let (duration, limit) = match profile.charging_schedule.first() {
+        Some(schedule) => match schedule.charging_schedule_period.first() {
+            // limit is a decimal in recent rust-ocpp, go through its string value
+            Some(period) => (
+                schedule.duration.unwrap_or(0),
+                period.limit.to_string().parse::<f64>().unwrap_or(0.0),
+            ),
+            None => return afb_error!("ocpp-occurrence-constraint", "empty schedule period"),
+        },
+        None => return afb_error!("ocpp-occurrence-constraint", "empty charging schedule"),
+    };
+
+    afb_log_msg!(Debug, rqt, "Backend set-charging-profile {:?}", value);
+    let limit = PowerLimit {
+        tid: target_tid,
+        imax: (limit * 100.0).round() as i32,
+        duration: duration as u32,
+    };
+
+    // evse 0 targets the station, route to the evse running the transaction
+    let cid = match value.evse_id {
+        0 => ctx.mgr.find_tid(target_tid).unwrap_or(ctx.mgr.get_cid()),
+        cid => cid.max(0) as u32,
+    };
+    let status = match ctx.mgr.set_limit(cid, limit)? {
+        v106::ChargingProfileStatus::Accepted => v201::ChargingProfileStatusEnumType::Accepted,
+        _ => v201::ChargingProfileStatusEnumType::Rejected,
+    };
+    let response = v201::SetChargingProfileResponse {
+        status,
+        status_info: None,
+    };
+    rqt.reply(v201::SetChargingProfileKind::Response(response), 0);
+    Ok(())
+}
+
+struct RequestStopTransactionCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    hook: Option<ResetHook>,
+    soft_error: bool,
+}
+backend_verb!(
+    request_stop_transaction_cb,
+    request_stop_transaction_action,
+    RequestStopTransactionCtx,
+    OcppVersion::V201
+);
+fn request_stop_transaction_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &RequestStopTransactionCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v201::StopTransactionRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend Request Stop Transaction {:?}", value);
+    // transaction id was generated by frontend from i32 counter
+    let tid = value.transaction_id.parse::<i32>().unwrap_or(0);
+    let cid = ctx.mgr.remote_stop_transaction(tid)?;
+    let status = match cid {
+        Some(_) => v201::RequestStartStopStatusEnumType::Accepted,
+        None => v201::RequestStartStopStatusEnumType::Rejected,
+    };
+    let response = v201::StopTransactionResponse {
+        status,
+        status_info: None,
+    };
+    rqt.reply(v201::RequestStopTransactionKind::Response(response), 0);
+
+    if let Some(cid) = cid {
+        if let Err(error) = remote_stop_send(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, ctx.auth, ctx.hook, cid, tid) {
+            afb_log_msg!(Warning, rqt, "Remote stop tid:{} failed {}", tid, error.get_info());
+        }
+    }
+    Ok(())
+}
+
+struct ChangeAvailabilityCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    soft_error: bool,
+}
+backend_verb!(
+    change_availability_cb,
+    change_availability_action,
+    ChangeAvailabilityCtx,
+    OcppVersion::V201
+);
+fn change_availability_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &ChangeAvailabilityCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v201::ChangeAvailabilityRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend change availability {:?}", value);
+    let operative = match value.operational_status {
+        v201::OperationalStatusEnumType::Operative => true,
+        v201::OperationalStatusEnumType::Inoperative => false,
+    };
+
+    // no evse targets the whole station
+    let cid = value.evse.as_ref().map_or(0, |evse| evse.id).max(0) as u32;
+    let (status, changed) = ctx.mgr.change_availability(cid, operative)?;
+    let status = match status {
+        v106::AvailabilityStatus::Accepted => v201::ChangeAvailabilityStatusEnumType::Accepted,
+        v106::AvailabilityStatus::Rejected => v201::ChangeAvailabilityStatusEnumType::Rejected,
+        v106::AvailabilityStatus::Scheduled => v201::ChangeAvailabilityStatusEnumType::Scheduled,
+    };
+    let response = v201::ChangeAvailabilityResponse {
+        status,
+        status_info: None,
+    };
+    rqt.reply(v201::ChangeAvailabilityKind::Response(response), 0);
+
+    // already replied, errors are only logged
+    for (cid, status) in changed {
+        if let Err(error) = status_notification_send(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, cid, &status) {
+            afb_log_msg!(Warning, rqt, "StatusNotification failed connector:{} {}", cid, error.get_info());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn register_backend_v201(
+    api: &mut AfbApi,
+    config: &BindingConfig,
+) -> Result<(), AfbError> {
+    let cancel_resa = AfbVerb::new("CancelReservation")
+        .set_callback(cancel_reservation_cb)
+        .set_context(CancelReservationCtx {
+            mgr: config.mgr,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend cancel reservation")
+        .finalize()?;
+
+    let reserve_now = AfbVerb::new("ReserveNow")
+        .set_callback(reserve_now_cb)
+        .set_context(ReserveNowCtx {
+            mgr: config.mgr,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend frontend reservation")
+        .finalize()?;
+
+    let reset = AfbVerb::new("Reset")
+        .set_callback(reset_cb)
+        .set_context(ResetVerbCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            hook: config.reset_hook,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request frontend reset")
+        .finalize()?;
+
+    let request_stop_transaction = AfbVerb::new("RequestStopTransaction")
+        .set_callback(request_stop_transaction_cb)
+        .set_context(RequestStopTransactionCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            auth: config.auth,
+            hook: config.reset_hook,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request to stop transaction")
+        .finalize()?;
+
+    let change_availability = AfbVerb::new("ChangeAvailability")
+        .set_callback(change_availability_cb)
+        .set_context(ChangeAvailabilityCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend change evse availability")
+        .finalize()?;
+
+    let setprofile = AfbVerb::new("SetChargingProfile")
+        .set_callback(set_charging_profile_cb)
+        .set_context(SetChargingProfileCtx {
+            mgr: config.mgr,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request SetChargingProfile")
+        .finalize()?;
+
+    api.add_verb(cancel_resa);
+    api.add_verb(reserve_now);
+    api.add_verb(setprofile);
+    api.add_verb(reset);
+    api.add_verb(request_stop_transaction);
+    api.add_verb(change_availability);
+    register_not_implemented(api, config, &V201_NOT_IMPLEMENTED)?;
+
+    Ok(())
+}
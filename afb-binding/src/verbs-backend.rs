@@ -13,6 +13,7 @@
 use crate::prelude::*;
 use afbv4::prelude::*;
 use ocpp::prelude::*;
+use std::process::Command;
 use std::time::Duration;
 use typesv4::prelude::*;
 
@@ -23,6 +24,96 @@ fn ignore_backend_rsp(rqt: &AfbRequest, _args: &AfbRqtData) -> Result<(), AfbErr
     Ok(())
 }
 
+// map action error to OCPP CALLERROR, OCPP-REC transport sends a CALLERROR on negative status
+pub(crate) fn backend_error(
+    rqt: &AfbRequest,
+    version: OcppVersion,
+    soft_error: bool,
+    error: &AfbError,
+) {
+    let callerror = OcppCallError::from_error(error, version);
+    if soft_error {
+        // some csms (e.g. biapower) cut connection on CALLERROR, answer an empty result
+        afb_log_msg!(
+            Warning,
+            rqt,
+            "Soft backend error code:{} {}",
+            callerror.code,
+            callerror.description
+        );
+        rqt.reply(AFB_NO_DATA, 0);
+        return;
+    }
+
+    afb_log_msg!(
+        Warning,
+        rqt,
+        "Backend CALLERROR code:{} {}",
+        callerror.code,
+        callerror.description
+    );
+    match JsoncObj::parse(&callerror.to_value().to_string()) {
+        Ok(jsonc) => rqt.reply(jsonc, -1),
+        Err(_) => rqt.reply(AFB_NO_DATA, -1),
+    }
+}
+
+// csms initiated actions without implementation, answered with NotImplemented. Together with
+// registered verbs it covers every 1.6 action, including security whitepaper extension ones.
+const V106_NOT_IMPLEMENTED: [&str; 12] = [
+    "CertificateSigned",
+    "ClearChargingProfile",
+    "DataTransfer",
+    "DeleteCertificate",
+    "ExtendedTriggerMessage",
+    "GetCompositeSchedule",
+    "GetDiagnostics",
+    "GetInstalledCertificateIds",
+    "GetLog",
+    "InstallCertificate",
+    "SignedUpdateFirmware",
+    "UpdateFirmware",
+];
+
+struct NotImplementedCtx {
+    action: &'static str,
+    version: OcppVersion,
+    soft_error: bool,
+}
+fn not_implemented_cb(
+    rqt: &AfbRequest,
+    _args: &AfbRqtData,
+    ctx: &AfbCtxData,
+) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<NotImplementedCtx>()?;
+    let status: Result<(), AfbError> =
+        afb_error!("ocpp-not-implemented", "action:{} not implemented", ctx.action);
+    if let Err(error) = status {
+        backend_error(rqt, ctx.version, ctx.soft_error, &error);
+    }
+    Ok(())
+}
+
+pub(crate) fn register_not_implemented(
+    api: &mut AfbApi,
+    config: &BindingConfig,
+    actions: &[&'static str],
+) -> Result<(), AfbError> {
+    for action in actions {
+        let verb = AfbVerb::new(action)
+            .set_callback(not_implemented_cb)
+            .set_context(NotImplementedCtx {
+                action,
+                version: config.version,
+                soft_error: config.soft_error,
+            })
+            .set_info("not implemented, reply CALLERROR")
+            .finalize()?;
+        api.add_verb(verb);
+    }
+    Ok(())
+}
+
 struct HeartbeatCtxData {
     count: u32,
 }
@@ -35,326 +126,876 @@ fn heartbeat_cb(
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_mut::<HeartbeatCtxData>()?;
     
-    let data = args.get::<&v106::Heartbeat>(0)?;
-    match data {
-        v106::Heartbeat::Request(_data) => {
-            ctx.count = ctx.count + 1;
-            afb_log_msg!(Debug, rqt, "Heartbeat count:{}", ctx.count);
-            let data = v106::Heartbeat::Response(v106::HeartbeatResponse {
-                current_time: get_utc(),
-            });
-            rqt.reply(data, 0);
-        }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported reset request");
-            rqt.reply(AFB_NO_DATA, -1);
-        }
-    }
+    let _data = args_decode::<v106::HeartbeatRequest>(args, 0)?;
+    ctx.count = ctx.count + 1;
+    afb_log_msg!(Debug, rqt, "Heartbeat count:{}", ctx.count);
+    let data = v106::Heartbeat::Response(v106::HeartbeatResponse {
+        current_time: get_utc(),
+    });
+    rqt.reply(data, 0);
     Ok(())
 }
 
 struct CancelReservationCtx {
     mgr: &'static ManagerHandle,
+    soft_error: bool,
 }
 // AfbVerbRegister!(CancelReservationVerb, cancel_notification_cb, CancelReservationCtx);
-fn cancel_notification_cb(
+backend_verb!(
+    cancel_notification_cb,
+    cancel_notification_action,
+    CancelReservationCtx,
+    OcppVersion::V106
+);
+fn cancel_notification_action(
     rqt: &AfbRequest,
     args: &AfbRqtData,
-    ctx: &AfbCtxData,
+    ctx: &CancelReservationCtx,
 ) -> Result<(), AfbError> {
-    let ctx = ctx.get_ref::<CancelReservationCtx>()?;
-    let data = args.get::<&v106::CancelReservation>(0)?;
-    match data {
-        v106::CancelReservation::Request(value) => {
-            afb_log_msg!(Debug, rqt, "Backend cancel reservation{:?}", value);
-            let status = ctx.mgr.reserv_cancel(value.reservation_id)?;
-            let response = v106::CancelReservationResponse { status };
-            rqt.reply(v106::CancelReservation::Response(response), 0);
-        }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported reset request");
-            rqt.reply(AFB_NO_DATA, -1);
-        }
-    }
+    let value = args_decode::<v106::CancelReservationRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend cancel reservation{:?}", value);
+    let status = ctx.mgr.reserv_cancel(value.reservation_id)?;
+    let response = v106::CancelReservationResponse { status };
+    rqt.reply(v106::CancelReservation::Response(response), 0);
     Ok(())
 }
 
 // 6.37. ReserveNow.req
 struct ReserveNowCtx {
     mgr: &'static ManagerHandle,
+    soft_error: bool,
 }
 // AfbVerbRegister!(ReserveNowVerb, reserve_now_cb, ReserveNowCtx);
-fn reserve_now_cb(
+backend_verb!(reserve_now_cb, reserve_now_action, ReserveNowCtx, OcppVersion::V106);
+fn reserve_now_action(
     rqt: &AfbRequest,
     args: &AfbRqtData,
-    ctx: &AfbCtxData,
+    ctx: &ReserveNowCtx,
 ) -> Result<(), AfbError> {
-    let ctx = ctx.get_ref::<ReserveNowCtx>()?;
-    let data = args.get::<&v106::ReserveNow>(0)?;
-    match data {
-        v106::ReserveNow::Request(value) => {
-            afb_log_msg!(Debug, rqt, "Backend reserve now {:?}", value);
-
-            let reservation = ReservationSession {
-                id: value.reservation_id,
-                tagid: value.id_tag.clone(),
-                start: Duration::new(0, 0),
-                stop: now_to_duration(value.expiry_date)?,
-                status: ReservationStatus::Pending,
-            };
+    let value = args_decode::<v106::ReserveNowRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend reserve now {:?}", value);
+
+    let reservation = ReservationSession {
+        id: value.reservation_id,
+        tagid: value.id_tag.clone(),
+        start: Duration::new(0, 0),
+        stop: now_to_duration(value.expiry_date)?,
+        status: ReservationStatus::Pending,
+    };
+
+    let status = ctx.mgr.reserv_now(value.connector_id, reservation)?;
+    let response = v106::ReserveNowResponse { status };
+    rqt.reply(v106::ReserveNow::Response(response), 0);
+    Ok(())
+}
 
-            let status = ctx.mgr.reserv_now(reservation)?;
-            let response = v106::ReserveNowResponse { status };
-            rqt.reply(v106::ReserveNow::Response(response), 0);
-        }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported reset request");
-            rqt.reply(AFB_NO_DATA, -1);
+// 6.7. ChangeAvailability.req, state is persisted and StatusNotification sent after response
+struct ChangeAvailabilityCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    soft_error: bool,
+}
+// AfbVerbRegister!(ChangeAvailabilityVerb, change_availability_cb, ChangeAvailabilityCtx);
+backend_verb!(
+    change_availability_cb,
+    change_availability_action,
+    ChangeAvailabilityCtx,
+    OcppVersion::V106
+);
+fn change_availability_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &ChangeAvailabilityCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::ChangeAvailabilityRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend change availability {:?}", value);
+    let operative = match &value.kind {
+        v106::AvailabilityType::Operative => true,
+        v106::AvailabilityType::Inoperative => false,
+    };
+
+    let (status, changed) = ctx.mgr.change_availability(value.connector_id, operative)?;
+    let response = v106::ChangeAvailabilityResponse { status };
+    rqt.reply(v106::ChangeAvailability::Response(response), 0);
+
+    // already replied, errors are only logged
+    for (cid, status) in changed {
+        if let Err(error) = status_notification_send(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, cid, &status) {
+            afb_log_msg!(Warning, rqt, "StatusNotification failed connector:{} {}", cid, error.get_info());
         }
     }
     Ok(())
 }
 
-// AfbVerbRegister!(ChangeAvailabilityVerb, change_availability_cb);
-fn change_availability_cb(rqt: &AfbRequest, args: &AfbRqtData) -> Result<(), AfbError> {
-    let data = args.get::<&v106::ChangeAvailability>(0)?;
-    match data {
-        v106::ChangeAvailability::Request(value) => {
-            afb_log_msg!(Debug, rqt, "Backend cancel reservation{:?}", value);
-            let _id = value.connector_id;
-            match &value.kind {
-                v106::AvailabilityType::Operative => {}
-                v106::AvailabilityType::Inoperative => {}
-            }
+// delay letting Reset response reach csms before closing transactions and calling reset hook
+const RESET_DELAY_MS: u32 = 1000;
 
-            // Fulup TBD Do something status= Accepted|Rejected|Scheduled
-            let response = v106::ChangeAvailabilityResponse {
-                status: v106::AvailabilityStatus::Accepted,
-            };
-            rqt.reply(v106::ChangeAvailability::Response(response), 0);
+struct ResetTimerCtx {
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    hook: Option<ResetHook>,
+    reason: OcppStopReason,
+}
+// AfbTimerRegister!(ResetTimerCtrl, reset_timer_cb, ResetTimerCtx);
+fn reset_timer_cb(_timer: &AfbTimer, _decount: u32, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<ResetTimerCtx>()?;
+
+    // synchronous calls, transactions are closed on csms side before the hook runs
+    for cid in ctx.mgr.get_transactions() {
+        let query = ctx.cp.stop_transaction(ctx.mgr, cid, ctx.mgr.get_meter(cid)?, ctx.reason, None)?;
+        if let Err(error) = AfbSubCall::call_sync(ctx.apiv4, "OCPP-SND", query.action, query.to_jsonc()?) {
+            afb_log_msg!(Warning, None, "Reset StopTransaction connector:{} failed {}", cid, error.get_info());
         }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported reset request");
-            rqt.reply(AFB_NO_DATA, -1);
+    }
+    ctx.mgr.reset()?;
+
+    let kind = match ctx.reason {
+        OcppStopReason::HardReset => "hard",
+        _ => "soft",
+    };
+    match ctx.hook {
+        None => {}
+        Some(ResetHook::Verb(api, verb)) => {
+            afb_log_msg!(Notice, None, "Reset {} calling {}/{}", kind, api, verb);
+            AfbSubCall::call_sync(ctx.apiv4, api, verb, kind)?;
+        }
+        Some(ResetHook::Command(command)) => {
+            afb_log_msg!(Notice, None, "Reset {} running '{}'", kind, command);
+            if let Err(error) = Command::new("sh").arg("-c").arg(format!("{} {}", command, kind)).spawn() {
+                return afb_error!("ocpp-reset-hook", "command:'{}' failed {}", command, error);
+            }
         }
     }
     Ok(())
 }
 
+// shared by 1.6 and 2.0.1 Reset, call after replying to csms
+pub(crate) fn reset_station(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    hook: Option<ResetHook>,
+    reason: OcppStopReason,
+) -> Result<(), AfbError> {
+    AfbTimer::new("reset-timer")
+        .set_period(RESET_DELAY_MS)
+        .set_decount(1)
+        .set_callback(reset_timer_cb)
+        .set_context(ResetTimerCtx {
+            apiv4,
+            mgr,
+            cp,
+            hook,
+            reason,
+        })
+        .start()?;
+    Ok(())
+}
+
+// 6.39. Reset.req, running transactions are stopped then reset hook is called
 struct ResetVerbCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    hook: Option<ResetHook>,
+    soft_error: bool,
 }
 // AfbVerbRegister!(ResetVerb, reset_cb, ResetVerbCtx);
-
-
-fn reset_cb(
-        rqt: &AfbRequest,
-        args: &AfbRqtData,
-        ctx: &AfbCtxData,
+backend_verb!(reset_cb, reset_action, ResetVerbCtx, OcppVersion::V106);
+fn reset_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &ResetVerbCtx,
 ) -> Result<(), AfbError> {
-    let ctx = ctx.get_ref::<ResetVerbCtx>()?;
-    let data = args.get::<&v106::Reset>(0)?;
-    match data {
-        v106::Reset::Request(reset) => {
-            afb_log_msg!(Debug, rqt, "Backend reset {:?}", reset);
-            let status = match reset.kind {
-                v106::ResetRequestStatus::Hard => {
-                    // should reboot hardware
-                    afb_log_msg!(Warning, rqt, "Hard reset (hardware reboot) ignored");
-                    v106::ResetResponseStatus::Rejected
-                }
-                v106::ResetRequestStatus::Soft => {
-                    ctx.mgr.reset()?;
-                    v106::ResetResponseStatus::Accepted
-                }
-            };
+    let reset = args_decode::<v106::ResetRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend reset {:?}", reset);
+    let reason = match reset.kind {
+        v106::ResetRequestStatus::Hard => OcppStopReason::HardReset,
+        v106::ResetRequestStatus::Soft => OcppStopReason::SoftReset,
+    };
+
+    // hardware reboot requires a reset hook
+    if reason == OcppStopReason::HardReset && ctx.hook.is_none() {
+        afb_log_msg!(Warning, rqt, "Hard reset rejected (no reset_verb/reset_cmd)");
+        let response = v106::ResetResponse {
+            status: v106::ResetResponseStatus::Rejected,
+        };
+        rqt.reply(v106::Reset::Response(response), 0);
+        return Ok(());
+    }
 
-            let response = v106::ResetResponse { status };
-            rqt.reply(v106::Reset::Response(response), 0);
-        }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported reset request");
-            rqt.reply(AFB_NO_DATA, -1);
-        }
+    let response = v106::ResetResponse {
+        status: v106::ResetResponseStatus::Accepted,
+    };
+    rqt.reply(v106::Reset::Response(response), 0);
+    // already replied, errors are only logged
+    if let Err(error) = reset_station(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, ctx.hook, reason) {
+        afb_log_msg!(Warning, rqt, "Reset failed {}", error.get_info());
     }
     Ok(())
 }
 
+// chmgr only handles first schedule period
+fn profile_limit(profile: &v106::ChargingProfile, tid: i32) -> Result<PowerLimit, AfbError> {
+    let schedule = &profile.charging_schedule;
+    let duration = match schedule.duration {
+        Some(value) => value,
+        None => return afb_error!("ocpp-occurrence-constraint", "missing schedule duration"),
+    };
+    let limit = match schedule.charging_schedule_period.first() {
+        Some(period) => period.limit,
+        None => return afb_error!("ocpp-occurrence-constraint", "empty schedule period"),
+    };
+
+    Ok(PowerLimit {
+        tid,
+        imax: (limit * 100.0).round() as i32,
+        duration: duration as u32,
+    })
+}
+
 struct SetChargingProfileCtx {
     mgr: &'static ManagerHandle,
+    soft_error: bool,
 }
 // 6.43. SetChargingProfile.req
 // https://www.ampcontrol.io/ocpp-guide/how-to-use-smart-charging-with-ocpp
 // AfbVerbRegister!(SetChargingProfileVerb, set_charging_profile_cb, SetChargingProfileCtx);
-fn set_charging_profile_cb(
+backend_verb!(
+    set_charging_profile_cb,
+    set_charging_profile_action,
+    SetChargingProfileCtx,
+    OcppVersion::V106
+);
+fn set_charging_profile_action(
     rqt: &AfbRequest,
     args: &AfbRqtData,
-    ctx: &AfbCtxData,
+    ctx: &SetChargingProfileCtx,
 ) -> Result<(), AfbError> {
-    let ctx = ctx.get_ref::<SetChargingProfileCtx>()?;
-    let data = args.get::<&v106::SetChargingProfile>(0)?;
-    match data {
-        v106::SetChargingProfile::Request(value) => {
-            let target_tid = match value.cs_charging_profiles.transaction_id {
-                Some(value) => value,
-                None => -1,
-            };
+    let value = args_decode::<v106::SetChargingProfileRequest>(args, 0)?;
+    let target_tid = match value.cs_charging_profiles.transaction_id {
+        Some(value) => value,
+        None => -1,
+    };
+
+    // connector 0 targets the station, route to the connector running the transaction
+    let cid = match value.connector_id {
+        0 => ctx.mgr.find_tid(target_tid).unwrap_or(ctx.mgr.get_cid()),
+        cid => cid,
+    };
+
+    // to avoid log mess we try to kill any invalid transaction
+    let session_tid = ctx.mgr.get_tid(cid)?;
+    if target_tid != session_tid {
+        afb_log_msg!(
+            Notice,
+            rqt,
+            "Ignored set-charging-profile backend_tid:{} != session_tid:{}",
+            target_tid,
+            session_tid
+        );
+        let status = v106::ChargingProfileStatus::Rejected;
+        let response = v106::SetChargingProfileResponse { status };
+        rqt.reply(v106::SetChargingProfile::Response(response), 0);
+
+        // let force stop invalid transaction ID
+        let query = v106::StopTransactionRequest {
+            id_tag: None,
+            meter_stop: 0,
+            timestamp: get_utc(),
+            reason: None,
+            transaction_data: None,
+            transaction_id: target_tid,
+        };
+
+        AfbSubCall::call_sync(
+            rqt.get_api(),
+            "OCPP-SND",
+            "StopTransaction",
+            v106::StopTransaction::Request(query),
+        )?;
+        return Ok(());
+    }
 
-            // to avoid log mess we try to kill any invalid transaction
-            let session_tid = ctx.mgr.get_tid()?;
-            if target_tid != session_tid {
-                afb_log_msg!(
-                    Notice,
-                    rqt,
-                    "Ignored set-charging-profile backend_tid:{} != session_tid:{}",
-                    target_tid,
-                    session_tid
-                );
-                let status = v106::ChargingProfileStatus::Rejected;
-                let response = v106::SetChargingProfileResponse { status };
-                rqt.reply(v106::SetChargingProfile::Response(response), 0);
-
-                // let force stop invalid transaction ID
-                let query = v106::StopTransactionRequest {
-                    id_tag: None,
-                    meter_stop: 0,
-                    timestamp: get_utc(),
-                    reason: None,
-                    transaction_data: None,
-                    transaction_id: target_tid,
-                };
-
-                AfbSubCall::call_sync(
-                    rqt.get_api(),
-                    "OCPP-SND",
-                    "StopTransaction",
-                    v106::StopTransaction::Request(query),
-                )?;
-                return Ok(());
-            }
+    afb_log_msg!(
+        Debug,
+        rqt,
+        "Backend set-charging-profile accepted {:?}",
+        value
+    );
+    let limit = profile_limit(&value.cs_charging_profiles, target_tid)?;
+    let status = ctx.mgr.set_limit(cid, limit)?;
+    let response = v106::SetChargingProfileResponse { status };
+    rqt.reply(v106::SetChargingProfile::Response(response), 0);
 
-            afb_log_msg!(
-                Debug,
-                rqt,
-                "Backend set-charging-profile accepted {:?}",
-                value
-            );
-            let duration = value
-                .cs_charging_profiles
-                .charging_schedule
-                .duration
-                .unwrap();
-            let limit = value
-                .cs_charging_profiles
-                .charging_schedule
-                .charging_schedule_period[0]
-                .limit;
-
-            let limit = PowerLimit {
-                tid: target_tid,
-                imax: (limit * 100.0).round() as i32,
-                duration: duration as u32,
-            };
+    Ok(())
+}
 
-            let status = ctx.mgr.set_limit(limit)?;
-            let response = v106::SetChargingProfileResponse { status };
-            rqt.reply(v106::SetChargingProfile::Response(response), 0);
+// 6.23. GetConfiguration.req
+struct GetConfigurationCtx {
+    store: &'static OcppConfigStore,
+    soft_error: bool,
+}
+backend_verb!(
+    get_configuration_cb,
+    get_configuration_action,
+    GetConfigurationCtx,
+    OcppVersion::V106
+);
+fn get_configuration_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &GetConfigurationCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::GetConfigurationRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend get configuration {:?}", value);
+    let response = ctx.store.get_configuration(&value)?;
+    rqt.reply(v106::GetConfiguration::Response(response), 0);
+    Ok(())
+}
+
+// 6.9. ChangeConfiguration.req, changes apply live (e.g. HeartbeatInterval timer)
+struct ChangeConfigurationCtx {
+    store: &'static OcppConfigStore,
+    soft_error: bool,
+}
+backend_verb!(
+    change_configuration_cb,
+    change_configuration_action,
+    ChangeConfigurationCtx,
+    OcppVersion::V106
+);
+fn change_configuration_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &ChangeConfigurationCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::ChangeConfigurationRequest>(args, 0)?;
+    let response = ctx.store.change_configuration(&value)?;
+    afb_log_msg!(
+        Notice,
+        rqt,
+        "Backend change configuration key:{} value:{} status:{:?}",
+        value.key,
+        value.value,
+        response.status
+    );
+    rqt.reply(v106::ChangeConfiguration::Response(response), 0);
+    Ok(())
+}
+
+// 6.33. RemoteStartTransaction.req, transaction starts when EV is plugged (status Preparing)
+struct RemoteStartTransactionCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    store: &'static OcppConfigStore,
+    soft_error: bool,
+}
+// AfbVerbRegister!(RemoteStartTransaction, remote_start_transaction_cb, RemoteStartTransactionCtx);
+backend_verb!(
+    remote_start_transaction_cb,
+    remote_start_transaction_action,
+    RemoteStartTransactionCtx,
+    OcppVersion::V106
+);
+fn remote_start_transaction_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &RemoteStartTransactionCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::RemoteStartTransactionRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend Remote Start Transaction req {:?}", value);
+
+    // only TxProfile may come with a remote start, tid is set at transaction start
+    let limit = match &value.charging_profile {
+        None => None,
+        Some(profile) if profile.charging_profile_purpose == v106::ChargingProfilePurposeType::TxProfile => {
+            Some(profile_limit(profile, 0)?)
         }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported reset request");
-            rqt.reply(AFB_NO_DATA, 0); // if returning error bia will cut connection
+        Some(_) => {
+            afb_log_msg!(Notice, rqt, "Remote start rejected, charging profile is not a TxProfile");
+            let response = v106::RemoteStartTransactionResponse {
+                status: v106::RemoteStartStopStatus::Rejected,
+            };
+            rqt.reply(v106::RemoteStartTransaction::Response(response), 0);
+            return Ok(());
+        }
+    };
+
+    let remote = ctx.mgr.remote_start(value.connector_id, &value.id_tag, limit);
+    let status = match remote {
+        Some(_) => v106::RemoteStartStopStatus::Accepted,
+        None => v106::RemoteStartStopStatus::Rejected,
+    };
+    let response = v106::RemoteStartTransactionResponse { status };
+    rqt.reply(v106::RemoteStartTransaction::Response(response), 0);
+
+    // already replied, errors are only logged
+    if let Some((cid, nonce)) = remote {
+        if let Err(error) = remote_start_send(
+            rqt.get_api().get_apiv4(),
+            ctx.mgr,
+            ctx.cp,
+            ctx.auth,
+            ctx.store,
+            cid,
+            nonce,
+            &value.id_tag,
+        ) {
+            afb_log_msg!(Warning, rqt, "Remote start connector:{} failed {}", cid, error.get_info());
         }
     }
-
     Ok(())
 }
 
-// Fulup Verbs TDB
-// -----------------
-// 6.11. ClearCache.req
-// 6.13. ClearSetChargingProfile.req
-// 6.15. DataTransfer.req
-// 6.21. GetCompositeSchedule.req
-// 6.23. GetConfiguration.req
-// 6.25. GetDiagnostics.req
-// 6.27. GetLocalListVersion.req
-// 6.33. RemoteStartTransaction.req
-// 6.39. Reset.req
-// 6.41. SendLocalList.req
-// 6.51. TriggerMessage.req
-// 6.53. UnlockConnector.req
-// 6.55. UpdateFirmware.req
+// 6.35. RemoteStopTransaction.req, chmgr stops power then StopTransaction is sent with reason Remote
+struct RemoteStopTransactionCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    hook: Option<ResetHook>,
+    soft_error: bool,
+}
+// AfbVerbRegister!(RemoteStopTransaction, remote_stop_transaction_cb, RemoteStopTransactionCtx);
+backend_verb!(
+    remote_stop_transaction_cb,
+    remote_stop_transaction_action,
+    RemoteStopTransactionCtx,
+    OcppVersion::V106
+);
+fn remote_stop_transaction_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &RemoteStopTransactionCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::RemoteStopTransactionRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend Remote Stop Transaction req {:?}", value);
+    let tid = value.transaction_id;
+    let cid = ctx.mgr.remote_stop_transaction(tid)?;
+    let status = match cid {
+        Some(_) => v106::RemoteStartStopStatus::Accepted,
+        None => v106::RemoteStartStopStatus::Rejected,
+    };
+    let response = v106::RemoteStopTransactionResponse { status };
+    rqt.reply(v106::RemoteStopTransaction::Response(response), 0);
+
+    if let Some(cid) = cid {
+        if let Err(error) = remote_stop_send(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, ctx.auth, ctx.hook, cid, tid) {
+            afb_log_msg!(Warning, rqt, "Remote stop tid:{} failed {}", tid, error.get_info());
+        }
+    }
+    Ok(())
+}
 
+// 6.53. UnlockConnector.req, running transaction is stopped before chmgr unlocks the plug
+struct UnlockConnectorCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    chmgr_api: &'static str,
+    unlock_verb: &'static str,
+    hook: Option<ResetHook>,
+    soft_error: bool,
+}
+// AfbVerbRegister!(UnlockConnectorVerb, unlock_connector_cb, UnlockConnectorCtx);
+backend_verb!(
+    unlock_connector_cb,
+    unlock_connector_action,
+    UnlockConnectorCtx,
+    OcppVersion::V106
+);
+fn unlock_connector_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &UnlockConnectorCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::UnlockConnectorRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend unlock connector {:?}", value);
+    let cid = value.connector_id;
 
+    // no charging manager or unknown connector
+    let tid = match ctx.mgr.get_tid(cid) {
+        Ok(value) if cid != 0 && !ctx.chmgr_api.is_empty() => value,
+        _ => {
+            let response = v106::UnlockConnectorResponse {
+                status: v106::UnlockStatus::NotSupported,
+            };
+            rqt.reply(v106::UnlockConnector::Response(response), 0);
+            return Ok(());
+        }
+    };
 
+    if tid != 0 {
+        let meter = ctx.mgr.get_meter(cid)?;
+        let query = ctx.cp.stop_transaction(ctx.mgr, cid, meter, OcppStopReason::UnlockCommand, None)?;
+        if let Err(error) = AfbSubCall::call_sync(rqt, "OCPP-SND", query.action, query.to_jsonc()?) {
+            afb_log_msg!(Warning, rqt, "Unlock StopTransaction tid:{} failed {}", tid, error.get_info());
+        }
+        transaction_closed(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, ctx.hook, cid)?;
+    }
 
-// 6.33. RemoteStopTransaction.req   RMU
-struct RemoteStopTransactionCtx {
-    mgr: &'static ocpp::manager::ManagerHandle,
+    // chmgr returns an error when the plug remains locked
+    let status = match AfbSubCall::call_sync(rqt, ctx.chmgr_api, ctx.unlock_verb, cid) {
+        Ok(_) => v106::UnlockStatus::Unlocked,
+        Err(error) => {
+            afb_log_msg!(Warning, rqt, "Unlock connector:{} failed {}", cid, error.get_info());
+            v106::UnlockStatus::UnlockFailed
+        }
+    };
+    let response = v106::UnlockConnectorResponse { status };
+    rqt.reply(v106::UnlockConnector::Response(response), 0);
+    Ok(())
 }
-// AfbVerbRegister!(RemoteStopTransaction, remote_stop_transaction_cb, RemoteStopTransactionCtx);
 
+// 6.51. TriggerMessage.req, requested message is sent after the response
+struct TriggerMessageCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    identity: &'static OcppIdentity,
+    engy_api: &'static str,
+    soft_error: bool,
+}
+// AfbVerbRegister!(TriggerMessageVerb, trigger_message_cb, TriggerMessageCtx);
+backend_verb!(
+    trigger_message_cb,
+    trigger_message_action,
+    TriggerMessageCtx,
+    OcppVersion::V106
+);
+fn trigger_message_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &TriggerMessageCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::TriggerMessageRequest>(args, 0)?;
+    afb_log_msg!(Notice, rqt, "Backend trigger message {:?}", value);
+    let apiv4 = rqt.get_api().get_apiv4();
+
+    // connectors targeted by StatusNotification/MeterValues, empty rejects the request
+    let targets = match value.requested_message {
+        v106::MessageTrigger::StatusNotification => match value.connector_id {
+            None => status_connectors(ctx.mgr, ctx.cp),
+            Some(cid) if status_connectors(ctx.mgr, ctx.cp).contains(&cid) => vec![cid],
+            Some(_) => Vec::new(),
+        },
+        // MeterValues requires a meter source, connector 0 targets every connector
+        v106::MessageTrigger::MeterValues if !ctx.engy_api.is_empty() => match value.connector_id {
+            None => ctx.mgr.get_connectors(),
+            Some(cid) => ctx.mgr.get_targets(cid).unwrap_or_default(),
+        },
+        v106::MessageTrigger::MeterValues => Vec::new(),
+        _ => vec![0],
+    };
+
+    let status = if targets.is_empty() {
+        v106::TriggerMessageStatus::Rejected
+    } else {
+        v106::TriggerMessageStatus::Accepted
+    };
+    let response = v106::TriggerMessageResponse { status };
+    rqt.reply(v106::TriggerMessage::Response(response), 0);
+
+    if targets.is_empty() {
+        return Ok(());
+    }
 
+    // already replied, errors are only logged
+    if let Err(error) = trigger_message_send(apiv4, ctx, &value.requested_message, targets) {
+        afb_log_msg!(Warning, rqt, "Triggered {:?} failed {}", value.requested_message, error.get_info());
+    }
+    Ok(())
+}
 
-fn remote_stop_transaction_cb(
-        rqt: &AfbRequest,
-        args: &AfbRqtData,
-        ctx: &AfbCtxData,
+fn trigger_message_send(
+    apiv4: AfbApiV4,
+    ctx: &TriggerMessageCtx,
+    message: &v106::MessageTrigger,
+    targets: Vec<u32>,
 ) -> Result<(), AfbError> {
-    let ctx = ctx.get_ref::<RemoteStopTransactionCtx>()?;
-    let data = args.get::<&v106::RemoteStopTransaction>(0)?;
-    match data {
-        v106::RemoteStopTransaction::Request(value) => {
-            afb_log_msg!(Debug, rqt, "Backend Remote Stop Transaction req {:?}", value);
-            ctx.mgr.remote_stop_transaction(value.transaction_id)?;
-            let response= v106::RemoteStartStopStatus::Accepted;
-            rqt.reply(v106::RemoteStopTransaction::Response(v106::RemoteStopTransactionResponse {status: response}), 0);
+    match message {
+        v106::MessageTrigger::BootNotification => boot_trigger_send(apiv4, ctx.cp, ctx.identity)?,
+        v106::MessageTrigger::Heartbeat => heartbeat_send(apiv4, ctx.mgr, ctx.cp, ctx.auth)?,
+        v106::MessageTrigger::StatusNotification => {
+            for cid in targets {
+                let status = ctx.mgr.get_status(cid)?;
+                status_notification_send(apiv4, ctx.mgr, ctx.cp, cid, &status)?;
+            }
         }
-        _ => {
-            afb_log_msg!(Warning, rqt, "Unsupported remote stop request request");
-            let response= v106::RemoteStartStopStatus::Rejected;
-            rqt.reply(v106::RemoteStopTransaction::Response(v106::RemoteStopTransactionResponse {status: response}), 0);
+        v106::MessageTrigger::MeterValues => {
+            for cid in targets {
+                meter_values_send(apiv4, ctx.mgr, ctx.cp, ctx.engy_api, cid)?;
+            }
+        }
+        // no diagnostics upload nor firmware update running
+        v106::MessageTrigger::DiagnosticsStatusNotification => {
+            let query = OcppQuery::new(v106::DiagnosticsStatusNotificationRequest {
+                status: v106::DiagnosticsStatus::Idle,
+            })?;
+            AfbSubCall::call_async(
+                apiv4,
+                "OCPP-SND",
+                query.action,
+                query.to_jsonc()?,
+                ignore_timer_rsp,
+                IgnoreResponseCtx {},
+            )?;
+        }
+        v106::MessageTrigger::FirmwareStatusNotification => {
+            let query = OcppQuery::new(v106::FirmwareStatusNotificationRequest {
+                status: v106::FirmwareStatus::Idle,
+            })?;
+            AfbSubCall::call_async(
+                apiv4,
+                "OCPP-SND",
+                query.action,
+                query.to_jsonc()?,
+                ignore_timer_rsp,
+                IgnoreResponseCtx {},
+            )?;
         }
     }
     Ok(())
 }
 
+// 6.11. ClearCache.req, local authorization list is not affected
+struct ClearCacheCtx {
+    auth: &'static OcppAuth,
+    soft_error: bool,
+}
+// AfbVerbRegister!(ClearCacheVerb, clear_cache_cb, ClearCacheCtx);
+backend_verb!(
+    clear_cache_cb,
+    clear_cache_action,
+    ClearCacheCtx,
+    OcppVersion::V106
+);
+fn clear_cache_action(
+    rqt: &AfbRequest,
+    _args: &AfbRqtData,
+    ctx: &ClearCacheCtx,
+) -> Result<(), AfbError> {
+    let status = match ctx.auth.clear_cache() {
+        Ok(()) => v106::ClearCacheStatus::Accepted,
+        Err(error) => {
+            afb_log_msg!(Warning, rqt, "Clear authorization cache failed {}", error.get_info());
+            v106::ClearCacheStatus::Rejected
+        }
+    };
+    let response = v106::ClearCacheResponse { status };
+    rqt.reply(v106::ClearCache::Response(response), 0);
+    Ok(())
+}
+
+// 6.41. SendLocalList.req, Differential updates require a newer list version
+struct SendLocalListCtx {
+    auth: &'static OcppAuth,
+    soft_error: bool,
+}
+// AfbVerbRegister!(SendLocalListVerb, send_local_list_cb, SendLocalListCtx);
+backend_verb!(
+    send_local_list_cb,
+    send_local_list_action,
+    SendLocalListCtx,
+    OcppVersion::V106
+);
+fn send_local_list_action(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &SendLocalListCtx,
+) -> Result<(), AfbError> {
+    let value = args_decode::<v106::SendLocalListRequest>(args, 0)?;
+    afb_log_msg!(Debug, rqt, "Backend send local list version:{} type:{:?}", value.list_version, value.update_type);
+    let status = ctx.auth.send_local_list(&value)?;
+    let response = v106::SendLocalListResponse { status };
+    rqt.reply(v106::SendLocalList::Response(response), 0);
+    Ok(())
+}
+
+// 6.27. GetLocalListVersion.req
+struct GetLocalListVersionCtx {
+    auth: &'static OcppAuth,
+    soft_error: bool,
+}
+// AfbVerbRegister!(GetLocalListVersionVerb, get_local_list_version_cb, GetLocalListVersionCtx);
+backend_verb!(
+    get_local_list_version_cb,
+    get_local_list_version_action,
+    GetLocalListVersionCtx,
+    OcppVersion::V106
+);
+fn get_local_list_version_action(
+    rqt: &AfbRequest,
+    _args: &AfbRqtData,
+    ctx: &GetLocalListVersionCtx,
+) -> Result<(), AfbError> {
+    let response = v106::GetLocalListVersionResponse {
+        list_version: ctx.auth.get_list_version()?,
+    };
+    afb_log_msg!(Debug, rqt, "Backend get local list version:{}", response.list_version);
+    rqt.reply(v106::GetLocalListVersion::Response(response), 0);
+    Ok(())
+}
+
 pub(crate) fn register_backend(api: &mut AfbApi, config: &BindingConfig) -> Result<(), AfbError> {
     let cancel_resa = AfbVerb::new("CancelReservation")
         .set_callback(cancel_notification_cb)
-        .set_context(CancelReservationCtx { mgr: config.mgr })
+        .set_context(CancelReservationCtx {
+            mgr: config.mgr,
+            soft_error: config.soft_error,
+        })
         .set_info("backend cancel reservation")
         .finalize()?;
 
     let reserve_now = AfbVerb::new("ReserveNow")
         .set_callback(reserve_now_cb)
-        .set_context(ReserveNowCtx { mgr: config.mgr })
+        .set_context(ReserveNowCtx {
+            mgr: config.mgr,
+            soft_error: config.soft_error,
+        })
         .set_info("backend frontend reservation")
         .finalize()?;
 
     let reset = AfbVerb::new("Reset")
         .set_callback(reset_cb)
-        .set_context(ResetVerbCtx { mgr: config.mgr })
+        .set_context(ResetVerbCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            hook: config.reset_hook,
+            soft_error: config.soft_error,
+        })
         .set_info("backend request frontend reset")
         .finalize()?;
 
+    let remote_start_transaction = AfbVerb::new("RemoteStartTransaction")
+        .set_callback(remote_start_transaction_cb)
+        .set_context(RemoteStartTransactionCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            auth: config.auth,
+            store: config.store,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request to start transaction")
+        .finalize()?;
+
     let remote_stop_transaction = AfbVerb::new("RemoteStopTransaction")
         .set_callback(remote_stop_transaction_cb)
-        .set_context(RemoteStopTransactionCtx { mgr: config.mgr })
-	    .set_info("backend request to stop transaction")
+        .set_context(RemoteStopTransactionCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            auth: config.auth,
+            hook: config.reset_hook,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request to stop transaction")
         .finalize()?;
 
     let setprofile = AfbVerb::new("SetChargingProfile")
         .set_callback(set_charging_profile_cb)
-        .set_context(SetChargingProfileCtx { mgr: config.mgr })
+        .set_context(SetChargingProfileCtx {
+            mgr: config.mgr,
+            soft_error: config.soft_error,
+        })
         .set_info("backend request SetChargingProfile")
         .finalize()?;
 
+    let change_availability = AfbVerb::new("ChangeAvailability")
+        .set_callback(change_availability_cb)
+        .set_context(ChangeAvailabilityCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend change connector availability")
+        .finalize()?;
+
+    let unlock_connector = AfbVerb::new("UnlockConnector")
+        .set_callback(unlock_connector_cb)
+        .set_context(UnlockConnectorCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            chmgr_api: config.chmgr_api,
+            unlock_verb: config.unlock_verb,
+            hook: config.reset_hook,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request connector unlock")
+        .finalize()?;
+
+    let trigger_message = AfbVerb::new("TriggerMessage")
+        .set_callback(trigger_message_cb)
+        .set_context(TriggerMessageCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            auth: config.auth,
+            identity: config.identity,
+            engy_api: config.engy_api,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request to send a charge point message")
+        .finalize()?;
+
+    let clear_cache = AfbVerb::new("ClearCache")
+        .set_callback(clear_cache_cb)
+        .set_context(ClearCacheCtx {
+            auth: config.auth,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request to clear authorization cache")
+        .finalize()?;
+
+    let send_local_list = AfbVerb::new("SendLocalList")
+        .set_callback(send_local_list_cb)
+        .set_context(SendLocalListCtx {
+            auth: config.auth,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend update local authorization list")
+        .finalize()?;
+
+    let get_local_list_version = AfbVerb::new("GetLocalListVersion")
+        .set_callback(get_local_list_version_cb)
+        .set_context(GetLocalListVersionCtx {
+            auth: config.auth,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request local authorization list version")
+        .finalize()?;
+
+    let get_configuration = AfbVerb::new("GetConfiguration")
+        .set_callback(get_configuration_cb)
+        .set_context(GetConfigurationCtx {
+            store: config.store,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend request configuration keys")
+        .finalize()?;
+
+    let change_configuration = AfbVerb::new("ChangeConfiguration")
+        .set_callback(change_configuration_cb)
+        .set_context(ChangeConfigurationCtx {
+            store: config.store,
+            soft_error: config.soft_error,
+        })
+        .set_info("backend change configuration key")
+        .finalize()?;
+
     api.add_verb(cancel_resa);
     api.add_verb(reserve_now);
     api.add_verb(setprofile);
     api.add_verb(reset);
+    api.add_verb(remote_start_transaction);
     api.add_verb(remote_stop_transaction);
+    api.add_verb(change_availability);
+    api.add_verb(unlock_connector);
+    api.add_verb(trigger_message);
+    api.add_verb(clear_cache);
+    api.add_verb(send_local_list);
+    api.add_verb(get_local_list_version);
+    api.add_verb(get_configuration);
+    api.add_verb(change_configuration);
+    register_not_implemented(api, config, &V106_NOT_IMPLEMENTED)?;
 
     Ok(())
 }
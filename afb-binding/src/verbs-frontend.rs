@@ -13,256 +13,774 @@
 use crate::prelude::*;
 use afbv4::prelude::*;
 use ocpp::prelude::*;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU32, Ordering};
 use typesv4::prelude::*;
 
-struct IgnoreResponseCtx { // redundant
+pub(crate) struct IgnoreResponseCtx { // redundant
 }
 // AfbCallRegister!(IgnoreResponseCtrl, ignore_timer_rsp);
-fn ignore_timer_rsp(_api: &AfbApi, _args: &AfbRqtData, _ctx: &AfbCtxData) -> Result<(), AfbError> {
+pub(crate) fn ignore_timer_rsp(_api: &AfbApi, _args: &AfbRqtData, _ctx: &AfbCtxData) -> Result<(), AfbError> {
     // let _ctx = ctx.get_ref::<IgnoreResponseCtx>()?; // redundant
     Ok(())
 }
 
-
-struct MonitorEvtCtx {
+struct HeartbeatTimerRspCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
 }
-// AfbEventRegister!(MonitorEvtCtrl, monitor_event_cb, MonitorEvtCtx);
-fn monitor_event_cb(
-    evt: &AfbEventMsg,
-    args: &AfbRqtData,
-    ctx: &AfbCtxData,
+// background heartbeat, csms currentTime keeps local clock in sync, tags accepted and transactions
+// started offline are sent to csms
+// AfbCallRegister!(HeartbeatTimerRsp, heartbeat_timer_rsp, HeartbeatTimerRspCtx);
+fn heartbeat_timer_rsp(api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<HeartbeatTimerRspCtx>()?;
+    let current_time = ctx.cp.heartbeat_rsp(args_value(args, 0)?)?;
+    if clock_sync(current_time) {
+        afb_log_msg!(Notice, None, "Clock synchronised from csms offset:{}ms", clock_offset());
+    }
+    offline_reconcile(api.get_apiv4(), ctx.cp, ctx.auth)?;
+    offline_replay(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth)
+}
+
+struct OfflineAuthorizeRspCtx {
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    id_tag: String,
+}
+// Authorize of a tag accepted offline, answer only feeds the cache
+// AfbCallRegister!(OfflineAuthorizeRsp, offline_authorize_rsp, OfflineAuthorizeRspCtx);
+fn offline_authorize_rsp(_api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<OfflineAuthorizeRspCtx>()?;
+    let accepted = args_response(args, 0).and_then(|payload| {
+        auth_cache_update(ctx.auth, ctx.cp, &ctx.id_tag, &payload);
+        ctx.cp.authorize_rsp(payload)
+    });
+    match accepted {
+        Ok(true) => {}
+        Ok(false) => afb_log_msg!(Warning, None, "Offline accepted tag:{} rejected by csms", ctx.id_tag),
+        // csms CALLERROR is final, tag is not sent again
+        Err(error) if is_callerror(&error) => {
+            afb_log_msg!(Warning, None, "Offline accepted tag:{} dropped {}", ctx.id_tag, error.get_info())
+        }
+        // still offline, retried at next heartbeat
+        Err(_) => ctx.auth.set_offline(&ctx.id_tag)?,
+    }
+    Ok(())
+}
+
+// csms answers again, tags accepted while offline are checked with Authorize
+fn offline_reconcile(
+    apiv4: AfbApiV4,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
 ) -> Result<(), AfbError> {
-    let ctx = ctx.get_ref::<MonitorEvtCtx>()?;
-    let status = ctx.mgr.get_status()?;
-    let msg = args.get::<String>(0)?;
+    let tags = auth.take_offline()?;
+    for (index, id_tag) in tags.iter().enumerate() {
+        afb_log_msg!(Notice, None, "Reconcile offline accepted tag:{}", id_tag);
+        let status = cp.authorize(id_tag).and_then(|query| {
+            AfbSubCall::call_async(
+                apiv4,
+                "OCPP-SND",
+                query.action,
+                query.to_jsonc()?,
+                offline_authorize_rsp,
+                OfflineAuthorizeRspCtx { cp, auth, id_tag: id_tag.clone() },
+            )
+        });
+        // unsent tags are kept for next heartbeat
+        if let Err(error) = status {
+            for id_tag in &tags[index..] {
+                auth.set_offline(id_tag)?;
+            }
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+struct OfflineReplayRspCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    transaction: OcppOfflineTx,
+}
+// replayed StartTransaction returns csms transaction id, next queued message is sent on success
+// AfbCallRegister!(OfflineReplayRsp, offline_replay_rsp, OfflineReplayRspCtx);
+fn offline_replay_rsp(api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<OfflineReplayRspCtx>()?;
+    let transaction = &ctx.transaction;
+    let payload = match args_response(args, 0) {
+        Ok(value) => value,
+        // csms CALLERROR is final, message is dropped
+        Err(error) if is_callerror(&error) => {
+            ctx.mgr.offline_drop()?;
+            afb_log_msg!(Warning, None, "Offline transaction tid:{} dropped {}", transaction.tid, error.get_info());
+            return offline_replay(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth);
+        }
+        // still offline, retried at next heartbeat
+        Err(error) => {
+            afb_log_msg!(Warning, None, "Offline transaction tid:{} replay failed {}", transaction.tid, error.get_info());
+            return ctx.mgr.offline_failed();
+        }
+    };
+    if let Some(id_tag) = &transaction.id_tag {
+        auth_cache_update(ctx.auth, ctx.cp, id_tag, &payload);
+    }
+    let tid = if transaction.start {
+        ctx.cp.transaction_id(&payload)
+    } else {
+        ctx.cp.stop_transaction_rsp(payload).map(|_| None)
+    };
+    let tid = match tid {
+        Ok(value) => value,
+        // invalid answer, retried until TransactionMessageAttempts
+        Err(error) => {
+            if ctx.mgr.offline_rejected()?.is_none() {
+                afb_log_msg!(Warning, None, "Offline transaction tid:{} retry {}", transaction.tid, error.get_info());
+                return Ok(());
+            }
+            afb_log_msg!(Warning, None, "Offline transaction tid:{} dropped {}", transaction.tid, error.get_info());
+            return offline_replay(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth);
+        }
+    };
     afb_log_msg!(
-        Warning,
-        evt,
-        "monitor_evt ocpp server websocket reset evt:{:?} status:{:?}",
-        msg,
-        &status
+        Notice,
+        None,
+        "Offline transaction {} replayed connector:{} tid:{} csms tid:{:?}",
+        transaction.query.action,
+        transaction.cid,
+        transaction.tid,
+        tid
     );
+    ctx.mgr.offline_done(tid)?;
+    offline_replay(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth)
+}
 
-    let query = update_charger_status(ctx.mgr, &status)?;
+// csms answers again, transaction messages queued while offline are replayed in order
+fn offline_replay(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+) -> Result<(), AfbError> {
+    let transaction = match mgr.offline_next()? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    // StopTransaction built offline gets csms transaction id returned by replayed StartTransaction
+    let query = cp.transaction_replay(&transaction.query, transaction.tid);
+    let status = query.to_jsonc().and_then(|jsonc| {
+        AfbSubCall::call_async(
+            apiv4,
+            "OCPP-SND",
+            query.action,
+            jsonc,
+            offline_replay_rsp,
+            OfflineReplayRspCtx { mgr, cp, auth, transaction },
+        )
+    });
+    if let Err(error) = status {
+        mgr.offline_failed()?;
+        return Err(error);
+    }
+    Ok(())
+}
+
+// station (connector 0) first, 2.0.1 has no station level StatusNotification (evseId > 0)
+pub(crate) fn status_connectors(mgr: &ManagerHandle, cp: &dyn OcppChargePoint) -> Vec<u32> {
+    mgr.get_status_connectors()
+        .into_iter()
+        .filter(|cid| *cid != 0 || cp.get_version() == OcppVersion::V106)
+        .collect()
+}
+
+// heartbeat response syncs local clock
+pub(crate) fn heartbeat_send(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+) -> Result<(), AfbError> {
+    let query = cp.heartbeat()?;
     AfbSubCall::call_async(
-        evt.get_apiv4(),
+        apiv4,
         "OCPP-SND",
-        "StatusNotification",
-        v106::StatusNotification::Request(query),
+        query.action,
+        query.to_jsonc()?,
+        heartbeat_timer_rsp,
+        HeartbeatTimerRspCtx { mgr, cp, auth },
+    )?;
+    Ok(())
+}
+
+struct BootTriggerRspCtx {
+    cp: &'static dyn OcppChargePoint,
+}
+// triggered BootNotification, registration state is left untouched
+// AfbCallRegister!(BootTriggerRsp, boot_trigger_rsp, BootTriggerRspCtx);
+fn boot_trigger_rsp(_api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<BootTriggerRspCtx>()?;
+    let response = ctx.cp.boot_notification_rsp(args_value(args, 0)?)?;
+    afb_log_msg!(Notice, None, "Triggered BootNotification status:{:?}", response.status);
+    if clock_sync(response.current_time) {
+        afb_log_msg!(Notice, None, "Clock synchronised from csms offset:{}ms", clock_offset());
+    }
+    Ok(())
+}
+
+pub(crate) fn boot_trigger_send(
+    apiv4: AfbApiV4,
+    cp: &'static dyn OcppChargePoint,
+    identity: &OcppIdentity,
+) -> Result<(), AfbError> {
+    let query = cp.boot_notification(identity)?;
+    AfbSubCall::call_async(
+        apiv4,
+        "OCPP-SND",
+        query.action,
+        query.to_jsonc()?,
+        boot_trigger_rsp,
+        BootTriggerRspCtx { cp },
+    )?;
+    Ok(())
+}
+
+struct EngyReadRspCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    cid: u32,
+}
+// engy current state, forwarded to csms as MeterValues
+// AfbCallRegister!(EngyReadRsp, engy_read_rsp, EngyReadRspCtx);
+fn engy_read_rsp(api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<EngyReadRspCtx>()?;
+    let state = args.get::<&EnergyState>(0)?;
+    ctx.mgr.set_meter(ctx.cid, state.session)?;
+    let query = ctx.cp.meter_values(ctx.mgr, ctx.cid, state)?;
+    AfbSubCall::call_async(
+        api.get_apiv4(),
+        "OCPP-SND",
+        query.action,
+        query.to_jsonc()?,
         ignore_timer_rsp,
         IgnoreResponseCtx {},
     )?;
-
     Ok(())
 }
 
-struct TimerCtx {
+// read current energy state from engy_api then send MeterValues
+pub(crate) fn meter_values_send(
     apiv4: AfbApiV4,
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    engy_api: &str,
+    cid: u32,
+) -> Result<(), AfbError> {
+    AfbSubCall::call_async(
+        apiv4,
+        engy_api,
+        "state",
+        EnergyAction::READ,
+        engy_read_rsp,
+        EngyReadRspCtx { mgr, cp, cid },
+    )?;
+    Ok(())
 }
-// ping server every tic-ms to keep ocpp connection live (Warning: not supported by biapower backend)
-// AfbTimerRegister!(TimerCtrl, timer_cb, TimerCtx);
-fn timer_cb(_timer: &AfbTimer, _decount: u32, ctx: &AfbCtxData) -> Result<(), AfbError> {
-    // keep updating 'available' charger status for OCPP not to forget about us
-    let ctx = ctx.get_ref::<TimerCtx>()?;
-    let status = ctx.mgr.get_status()?;
-    match status {
-        OcppChargerStatus::Available => {}
-        _ => return Ok(()),
-    }
 
-    let query = update_charger_status(ctx.mgr, &status)?;
+// fire and forget StatusNotification (boot, availability change...), unsupported connector 0 is skipped
+pub(crate) fn status_notification_send(
+    apiv4: AfbApiV4,
+    mgr: &ManagerHandle,
+    cp: &dyn OcppChargePoint,
+    cid: u32,
+    status: &OcppChargerStatus,
+) -> Result<(), AfbError> {
+    if cid == 0 && !status_connectors(mgr, cp).contains(&0) {
+        return Ok(());
+    }
+    let query = cp.status_notification(cid, status)?;
     AfbSubCall::call_async(
-        ctx.apiv4,
+        apiv4,
         "OCPP-SND",
-        "StatusNotification",
-        v106::StatusNotification::Request(query),
+        query.action,
+        query.to_jsonc()?,
         ignore_timer_rsp,
         IgnoreResponseCtx {},
     )?;
     Ok(())
 }
 
-// init ocpp backend at API initialization time
-pub fn ocpp_bootstrap(
-    api: &AfbApi,
+// StatusNotification changed by ChangeAvailability received during transaction, then OnIdle reset
+pub(crate) fn transaction_closed(
+    apiv4: AfbApiV4,
     mgr: &'static ManagerHandle,
-    station: &str,
-    tic: u32,
+    cp: &'static dyn OcppChargePoint,
+    hook: Option<ResetHook>,
+    cid: u32,
 ) -> Result<(), AfbError> {
-    AfbSubCall::call_sync(
-        api,
+    mgr.logout(cid)?;
+    for (cid, status) in mgr.apply_scheduled(cid)? {
+        status_notification_send(apiv4, mgr, cp, cid, &status)?;
+    }
+    if let Some(reason) = mgr.reset_idle() {
+        afb_log_msg!(Notice, None, "Last transaction closed, running scheduled reset");
+        reset_station(apiv4, mgr, cp, hook, reason)?;
+    }
+    Ok(())
+}
+
+// feed authorization cache with idTagInfo received from csms
+fn auth_cache_update(auth: &OcppAuth, cp: &dyn OcppChargePoint, id_tag: &str, payload: &Value) {
+    if let Some(info) = cp.id_tag_info(payload) {
+        if let Err(error) = auth.cache_update(id_tag, info) {
+            afb_log_msg!(Warning, None, "Authorization cache update failed {}", error.get_info());
+        }
+    }
+}
+
+// StopTransaction idTagInfo refers to the presented tag, or to the tag which started an anonymous stop
+fn transaction_stopped(
+    mgr: &ManagerHandle,
+    cp: &dyn OcppChargePoint,
+    auth: &OcppAuth,
+    cid: u32,
+    id_tag: Option<&str>,
+    payload: Value,
+) -> Result<(), AfbError> {
+    let id_tag = match id_tag {
+        Some(value) => Some(value.to_string()),
+        None => mgr.get_id_tag(cid)?,
+    };
+    if let Some(id_tag) = id_tag {
+        auth_cache_update(auth, cp, &id_tag, &payload);
+    }
+    cp.stop_transaction_rsp(payload)
+}
+
+struct RemoteStopRspCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    hook: Option<ResetHook>,
+    cid: u32,
+}
+// AfbCallRegister!(RemoteStopRsp, remote_stop_rsp, RemoteStopRspCtx);
+fn remote_stop_rsp(api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<RemoteStopRspCtx>()?;
+    if let Err(error) =
+        args_value(args, 0).and_then(|payload| transaction_stopped(ctx.mgr, ctx.cp, ctx.auth, ctx.cid, None, payload))
+    {
+        afb_log_msg!(Warning, None, "Remote StopTransaction failed connector:{} {}", ctx.cid, error.get_info());
+    }
+    transaction_closed(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.hook, ctx.cid)
+}
+
+// chmgr should stop power and call 'transaction' stop within this delay
+const REMOTE_STOP_TIMEOUT_MS: u32 = 30000;
+
+struct RemoteStopTimerCtx {
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    hook: Option<ResetHook>,
+    cid: u32,
+    tid: i32,
+}
+// AfbTimerRegister!(RemoteStopTimerCtrl, remote_stop_timer_cb, RemoteStopTimerCtx);
+fn remote_stop_timer_cb(_timer: &AfbTimer, _decount: u32, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<RemoteStopTimerCtx>()?;
+    if !ctx.mgr.remote_stop_pending(ctx.cid, ctx.tid)? {
+        return Ok(());
+    }
+
+    // chmgr did not answer, close transaction with last pushed meter
+    afb_log_msg!(Warning, None, "Remote stop timeout tid:{} connector:{}", ctx.tid, ctx.cid);
+    let meter = ctx.mgr.get_meter(ctx.cid)?;
+    let reason = ctx.mgr.stop_reason(ctx.cid)?;
+    let query = ctx.cp.stop_transaction(ctx.mgr, ctx.cid, meter, reason, None)?;
+    AfbSubCall::call_async(
+        ctx.apiv4,
         "OCPP-SND",
-        "BootNotification",
-        v106::BootNotification::Request(v106::BootNotificationRequest {
-            charge_point_vendor: station.to_string(),
-            charge_point_model: "Tux-Evse OCPP-1.6".to_string(),
-            firmware_version: Some("v1234".to_string()),
-            charge_box_serial_number: None,
-            charge_point_serial_number: None,
-            iccid: None,
-            imsi: None,
-            meter_serial_number: None,
-            meter_type: None,
-        }),
+        query.action,
+        query.to_jsonc()?,
+        remote_stop_rsp,
+        RemoteStopRspCtx { mgr: ctx.mgr, cp: ctx.cp, auth: ctx.auth, hook: ctx.hook, cid: ctx.cid },
     )?;
+    Ok(())
+}
+
+// RemoteStopTransaction accepted, StopTransaction is sent when chmgr calls 'transaction' stop with final meter
+pub(crate) fn remote_stop_send(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    hook: Option<ResetHook>,
+    cid: u32,
+    tid: i32,
+) -> Result<(), AfbError> {
+    AfbTimer::new("remote-stop-timer")
+        .set_period(REMOTE_STOP_TIMEOUT_MS)
+        .set_decount(1)
+        .set_callback(remote_stop_timer_cb)
+        .set_context(RemoteStopTimerCtx { apiv4, mgr, cp, auth, hook, cid, tid })
+        .start()?;
+    Ok(())
+}
+
+struct RemoteStartRspCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    cid: u32,
+    nonce: u32,
+    id_tag: String,
+}
+// StartTransaction sent by the binding for a remote start, login notifies chmgr
+// AfbCallRegister!(RemoteStartRsp, remote_start_rsp, RemoteStartRspCtx);
+fn remote_start_rsp(_api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<RemoteStartRspCtx>()?;
+    let tid = args_value(args, 0).and_then(|payload| {
+        auth_cache_update(ctx.auth, ctx.cp, &ctx.id_tag, &payload);
+        ctx.cp.start_transaction_rsp(ctx.cid, payload)
+    });
+    match tid {
+        Ok(tid) => {
+            afb_log_msg!(Notice, None, "Remote transaction started tid:{} connector:{}", tid, ctx.cid);
+            ctx.mgr.login(ctx.cid, tid, &ctx.id_tag)?;
+        }
+        Err(error) => {
+            afb_log_msg!(Warning, None, "Remote StartTransaction failed connector:{} {}", ctx.cid, error.get_info());
+            ctx.mgr.remote_start_cancel(ctx.cid, ctx.nonce, false)?;
+        }
+    }
+    Ok(())
+}
 
-    AfbSubCall::call_sync(
-        api,
+// EV plugged (status Preparing) on a connector waiting for RemoteStartTransaction
+pub(crate) fn remote_start_plugged(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    cid: u32,
+) -> Result<(), AfbError> {
+    let (id_tag, nonce) = match mgr.remote_start_plugged(cid)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let query = cp.start_transaction(cid, &id_tag)?;
+    AfbSubCall::call_async(
+        apiv4,
         "OCPP-SND",
-        "StatusNotification",
-        v106::StatusNotification::Request(v106::StatusNotificationRequest {
-            connector_id: 1,
-            status: v106::ChargePointStatus::Available,
-            error_code: v106::ChargePointErrorCode::NoError,
-            info: Some("Tux-Evse booting report".to_string()),
-            timestamp: Some(get_utc()),
-            vendor_id: None,
-            vendor_error_code: None,
-        }),
+        query.action,
+        query.to_jsonc()?,
+        remote_start_rsp,
+        RemoteStartRspCtx { mgr, cp, auth, cid, nonce, id_tag },
     )?;
+    Ok(())
+}
 
-    AfbSubCall::call_sync(
-        api,
+fn remote_start_ready(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    cid: u32,
+    nonce: u32,
+) -> Result<(), AfbError> {
+    if mgr.remote_start_ready(cid, nonce)? {
+        remote_start_plugged(apiv4, mgr, cp, auth, cid)?;
+    }
+    Ok(())
+}
+
+struct RemoteAuthorizeRspCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    cid: u32,
+    nonce: u32,
+    id_tag: String,
+}
+// AfbCallRegister!(RemoteAuthorizeRsp, remote_authorize_rsp, RemoteAuthorizeRspCtx);
+fn remote_authorize_rsp(api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<RemoteAuthorizeRspCtx>()?;
+    let accepted = args_value(args, 0).and_then(|payload| {
+        auth_cache_update(ctx.auth, ctx.cp, &ctx.id_tag, &payload);
+        ctx.cp.authorize_rsp(payload)
+    });
+    let accepted = match accepted {
+        Ok(value) => value,
+        Err(error) => {
+            afb_log_msg!(Warning, None, "Remote start Authorize failed {}", error.get_info());
+            false
+        }
+    };
+    if !accepted {
+        afb_log_msg!(Notice, None, "Remote start rejected by Authorize connector:{}", ctx.cid);
+        ctx.mgr.remote_start_cancel(ctx.cid, ctx.nonce, false)?;
+        return Ok(());
+    }
+    remote_start_ready(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth, ctx.cid, ctx.nonce)
+}
+
+struct RemoteStartTimerCtx {
+    mgr: &'static ManagerHandle,
+    cid: u32,
+    nonce: u32,
+}
+// AfbTimerRegister!(RemoteStartTimerCtrl, remote_start_timer_cb, RemoteStartTimerCtx);
+fn remote_start_timer_cb(_timer: &AfbTimer, _decount: u32, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<RemoteStartTimerCtx>()?;
+    if ctx.mgr.remote_start_cancel(ctx.cid, ctx.nonce, true)? {
+        afb_log_msg!(Notice, None, "Remote start timeout connector:{}", ctx.cid);
+    }
+    Ok(())
+}
+
+// RemoteStartTransaction accepted, AuthorizeRemoteTxRequests sends Authorize before notifying chmgr
+// remote start is cancelled when no transaction started within ConnectionTimeOut
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn remote_start_send(
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    store: &'static OcppConfigStore,
+    cid: u32,
+    nonce: u32,
+    id_tag: &str,
+) -> Result<(), AfbError> {
+    let timeout = store.get_u32(CFG_CONNECTION_TIMEOUT)?;
+    if timeout > 0 {
+        AfbTimer::new("remote-start-timer")
+            .set_period(timeout.saturating_mul(1000))
+            .set_decount(1)
+            .set_callback(remote_start_timer_cb)
+            .set_context(RemoteStartTimerCtx { mgr, cid, nonce })
+            .start()?;
+    }
+
+    if !store.get_bool(CFG_AUTHORIZE_REMOTE_TX_REQUESTS)? {
+        return remote_start_ready(apiv4, mgr, cp, auth, cid, nonce);
+    }
+    let query = cp.authorize(id_tag)?;
+    AfbSubCall::call_async(
+        apiv4,
         "OCPP-SND",
-        "Heartbeat",
-        v106::Heartbeat::Request(v106::HeartbeatRequest {}),
+        query.action,
+        query.to_jsonc()?,
+        remote_authorize_rsp,
+        RemoteAuthorizeRspCtx { mgr, cp, auth, cid, nonce, id_tag: id_tag.to_string() },
     )?;
+    Ok(())
+}
 
-    if tic > 0 {
-        AfbTimer::new("tic-timer")
-            .set_period(tic)
-            .set_decount(0)
-            .set_callback(timer_cb)
-            .set_context(TimerCtx {
-                apiv4: api.get_apiv4(),
-                mgr,
-            })
-            .start()?;
+struct MonitorEvtCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+}
+// AfbEventRegister!(MonitorEvtCtrl, monitor_event_cb, MonitorEvtCtx);
+fn monitor_event_cb(
+    evt: &AfbEventMsg,
+    args: &AfbRqtData,
+    ctx: &AfbCtxData,
+) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<MonitorEvtCtx>()?;
+    let msg = args.get::<String>(0)?;
+    afb_log_msg!(
+        Warning,
+        evt,
+        "monitor_evt ocpp server websocket reset evt:{:?}",
+        msg
+    );
+
+    // boot retry timer resends everything once csms accepts the charge point
+    if ctx.mgr.get_boot() != OcppBootStatus::Accepted {
+        return Ok(());
     }
+
+    for cid in status_connectors(ctx.mgr, ctx.cp) {
+        let status = ctx.mgr.get_status(cid)?;
+        status_notification_send(evt.get_apiv4(), ctx.mgr, ctx.cp, cid, &status)?;
+    }
+
     Ok(())
 }
 
-fn update_charger_status(
-    mgr: &ManagerHandle,
-    status: &OcppChargerStatus,
-) -> Result<v106::StatusNotificationRequest, AfbError> {
-    let mut error_code = v106::ChargePointErrorCode::NoError;
-
-    let charger_status = match status {
-        OcppChargerStatus::Charging => v106::ChargePointStatus::Charging,
-        OcppChargerStatus::Reserved => v106::ChargePointStatus::Reserved,
-        OcppChargerStatus::Unavailable => v106::ChargePointStatus::Unavailable,
-        OcppChargerStatus::Available => v106::ChargePointStatus::Available,
-        OcppChargerStatus::Finishing => v106::ChargePointStatus::Finishing,
-        OcppChargerStatus::Preparing => v106::ChargePointStatus::Preparing,
-        OcppChargerStatus::Error(err_code) => {
-            error_code = match err_code {
-                OcppErrorCode::ConnectorLockFailure => {
-                    v106::ChargePointErrorCode::ConnectorLockFailure
-                }
-                OcppErrorCode::GroundFailure => v106::ChargePointErrorCode::GroundFailure,
-                OcppErrorCode::HighTemperature => v106::ChargePointErrorCode::HighTemperature,
-                OcppErrorCode::InternalError => v106::ChargePointErrorCode::InternalError,
-                OcppErrorCode::NoError => v106::ChargePointErrorCode::NoError,
-                OcppErrorCode::OtherError => v106::ChargePointErrorCode::OtherError,
-                OcppErrorCode::OverCurrentFailure => v106::ChargePointErrorCode::OverCurrentFailure,
-                OcppErrorCode::OverVoltage => v106::ChargePointErrorCode::OverVoltage,
-                OcppErrorCode::PowerMeterFailure => v106::ChargePointErrorCode::PowerMeterFailure,
-                OcppErrorCode::PowerSwitchFailure => v106::ChargePointErrorCode::PowerSwitchFailure,
-                OcppErrorCode::ReaderFailure => v106::ChargePointErrorCode::ReaderFailure,
-                OcppErrorCode::UnderVoltage => v106::ChargePointErrorCode::UnderVoltage,
-                OcppErrorCode::WeakSignal => v106::ChargePointErrorCode::WeakSignal,
-            };
-            v106::ChargePointStatus::Faulted
+// timer base tick, HeartbeatInterval is read at each tick to apply csms changes live
+const TIMER_TICK_MS: u32 = 1000;
+
+struct BootNotificationRspCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    store: &'static OcppConfigStore,
+    auth: &'static OcppAuth,
+}
+// csms registration response, interval is the retry delay until Accepted then the heartbeat period
+// AfbCallRegister!(BootNotificationRsp, boot_notification_rsp, BootNotificationRspCtx);
+fn boot_notification_rsp(api: &AfbApi, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<BootNotificationRspCtx>()?;
+    let response = match args_value(args, 0).and_then(|payload| ctx.cp.boot_notification_rsp(payload)) {
+        Ok(value) => value,
+        Err(error) => {
+            afb_log_msg!(Warning, None, "BootNotification failed (retry in {}s) {}", BOOT_RETRY_DEFAULT, error.get_info());
+            ctx.mgr.boot_update(OcppBootStatus::Idle, 0);
+            return Ok(());
         }
     };
 
-    let query = v106::StatusNotificationRequest {
-        connector_id: mgr.get_cid(),
-        error_code: error_code, // IdToken, should this be a type?
-        status: charger_status,
-        info: None,
-        timestamp: Some(get_utc()),
-        vendor_id: None,
-        vendor_error_code: None,
-    };
+    afb_log_msg!(
+        Notice,
+        None,
+        "BootNotification status:{:?} interval:{}s time:{}",
+        response.status,
+        response.interval,
+        response.current_time
+    );
+    if clock_sync(response.current_time) {
+        afb_log_msg!(Notice, None, "Clock synchronised from csms offset:{}ms", clock_offset());
+    }
+    ctx.mgr.boot_update(response.status, response.interval);
+    if response.status != OcppBootStatus::Accepted {
+        return Ok(());
+    }
+
+    if response.interval > 0 {
+        ctx.store.set_value(CFG_HEARTBEAT_INTERVAL, &response.interval.to_string())?;
+    }
 
-    Ok(query)
+    for cid in status_connectors(ctx.mgr, ctx.cp) {
+        // connectors start Available, inoperative ones (persisted ChangeAvailability) stay Unavailable
+        let status = ctx.mgr.set_status(cid, &OcppChargerStatus::Available)?;
+        status_notification_send(api.get_apiv4(), ctx.mgr, ctx.cp, cid, &status)?;
+    }
+    offline_replay(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth)?;
+    heartbeat_send(api.get_apiv4(), ctx.mgr, ctx.cp, ctx.auth)
 }
 
-// ref: https://www.ampcontrol.io/ocpp-guide/how-to-send-ocpp-meter-values-with-metervalues-req
-fn engy_event_action(
-    state: &EnergyState,
+fn boot_notification_send(
+    apiv4: AfbApiV4,
     mgr: &'static ManagerHandle,
-) -> Result<v106::MeterValuesRequest, AfbError> {
-    let tid = mgr.get_tid()?;
-    if tid == 0 {
-        return afb_error!("ocpp-energy-state", "not active transaction running");
-    }
-
-    let tension_value = v106::SampledValue {
-        value: (state.tension / 1000).to_string(),
-        location: None, // string
-        context: None,  // string
-        phase: None,    // default L1
-        format: None,   // default raw
-        unit: Some(v106::UnitOfMeasure::V),
-        measurand: Some(v106::Measurand::Voltage),
-    };
+    cp: &'static dyn OcppChargePoint,
+    store: &'static OcppConfigStore,
+    auth: &'static OcppAuth,
+    identity: &OcppIdentity,
+) -> Result<(), AfbError> {
+    let query = cp.boot_notification(identity)?;
+    mgr.boot_sent();
+    if let Err(error) = AfbSubCall::call_async(
+        apiv4,
+        "OCPP-SND",
+        query.action,
+        query.to_jsonc()?,
+        boot_notification_rsp,
+        BootNotificationRspCtx { mgr, cp, store, auth },
+    ) {
+        mgr.boot_update(OcppBootStatus::Idle, 0);
+        return Err(error);
+    }
+    Ok(())
+}
 
-    let current_value = v106::SampledValue {
-        value: (state.current / 1000).to_string(),
-        location: None, // string
-        context: None,  // string
-        phase: None,    // default L1
-        format: None,   // default Raw
-        unit: Some(v106::UnitOfMeasure::A),
-        measurand: Some(v106::Measurand::CurrentImport),
-    };
+struct TimerCtx {
+    apiv4: AfbApiV4,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    store: &'static OcppConfigStore,
+    auth: &'static OcppAuth,
+    identity: &'static OcppIdentity,
+    elapsed: AtomicU32,
+}
+// retry BootNotification until accepted, then ping server every HeartbeatInterval to keep ocpp connection live
+// (Warning: not supported by biapower backend)
+// AfbTimerRegister!(TimerCtrl, timer_cb, TimerCtx);
+fn timer_cb(_timer: &AfbTimer, _decount: u32, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<TimerCtx>()?;
+    if ctx.mgr.boot_tick() {
+        boot_notification_send(ctx.apiv4, ctx.mgr, ctx.cp, ctx.store, ctx.auth, ctx.identity)?;
+    }
 
-    let power_value = v106::SampledValue {
-        value: (state.power / 1000).to_string(),
-        location: None, // string
-        context: None,  // string
-        phase: None,    // default L1
-        format: None,   // default Raw
-        unit: Some(v106::UnitOfMeasure::W),
-        measurand: Some(v106::Measurand::PowerActiveImport),
-    };
+    // replay delayed by an invalid csms answer restarts after TransactionMessageRetryInterval
+    if ctx.mgr.offline_tick()? && ctx.mgr.get_boot() == OcppBootStatus::Accepted {
+        offline_replay(ctx.apiv4, ctx.mgr, ctx.cp, ctx.auth)?;
+    }
 
-    let session_value = v106::SampledValue {
-        value: (state.session).to_string(),
-        location: None, // string
-        context: None,  // string
-        phase: None,    // default L1
-        format: None,   // default Raw
-        unit: Some(v106::UnitOfMeasure::Wh),
-        measurand: Some(v106::Measurand::EnergyActiveImportRegister),
-    };
+    let interval = ctx.store.get_u32(CFG_HEARTBEAT_INTERVAL)?;
+    if interval == 0 || ctx.mgr.get_boot() != OcppBootStatus::Accepted {
+        ctx.elapsed.store(0, Ordering::Relaxed);
+        return Ok(());
+    }
 
-    let query = v106::MeterValuesRequest {
-        connector_id: mgr.get_cid(),
-        transaction_id: Some(tid),
-        meter_value: vec![v106::MeterValue {
-            timestamp: get_utc(),
-            sampled_value: vec![tension_value, power_value, current_value, session_value],
-        }],
-    };
-    Ok(query)
+    if ctx.elapsed.fetch_add(1, Ordering::Relaxed) + 1 < interval {
+        return Ok(());
+    }
+    ctx.elapsed.store(0, Ordering::Relaxed);
+
+    for query in ctx.cp.keep_alive(ctx.mgr)? {
+        if query.action == "Heartbeat" {
+            AfbSubCall::call_async(
+                ctx.apiv4,
+                "OCPP-SND",
+                query.action,
+                query.to_jsonc()?,
+                heartbeat_timer_rsp,
+                HeartbeatTimerRspCtx { mgr: ctx.mgr, cp: ctx.cp, auth: ctx.auth },
+            )?;
+        } else {
+            AfbSubCall::call_async(
+                ctx.apiv4,
+                "OCPP-SND",
+                query.action,
+                query.to_jsonc()?,
+                ignore_timer_rsp,
+                IgnoreResponseCtx {},
+            )?;
+        }
+    }
+    Ok(())
 }
 
-struct MeterValuesRspCtx { // redundant
+// init ocpp backend at API initialization time, StatusNotification and Heartbeat wait for boot acceptance
+pub fn ocpp_bootstrap(
+    api: &AfbApi,
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    store: &'static OcppConfigStore,
+    auth: &'static OcppAuth,
+    identity: &'static OcppIdentity,
+) -> Result<(), AfbError> {
+    boot_notification_send(api.get_apiv4(), mgr, cp, store, auth, identity)?;
+
+    // always running, drives boot retries and HeartbeatInterval (0 disables keep alive)
+    AfbTimer::new("tic-timer")
+        .set_period(TIMER_TICK_MS)
+        .set_decount(0)
+        .set_callback(timer_cb)
+        .set_context(TimerCtx {
+            apiv4: api.get_apiv4(),
+            mgr,
+            cp,
+            store,
+            auth,
+            identity,
+            elapsed: AtomicU32::new(0),
+        })
+        .start()?;
+    Ok(())
+}
+
+struct MeterValuesRspCtx {
+    cp: &'static dyn OcppChargePoint,
 }
 // async callback for pushing measures
 // AfbVerbRegister!(MeterValuesRsp, meter_values_response);
-fn meter_values_response(rqt: &AfbRequest, args: &AfbRqtData, _ctx: &AfbCtxData) -> Result<(), AfbError> {
-    let data = args.get::<&v106::MeterValues>(0)?;
-    match data {
-        v106::MeterValues::Response(response) => response,
-        _ => return afb_error!("ocpp-metervalue-rsp", "invalid response type"),
-    };
+fn meter_values_response(rqt: &AfbRequest, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<MeterValuesRspCtx>()?;
+    ctx.cp.meter_values_rsp(args_value(args, 0)?)?;
     afb_log_msg!(Debug, rqt, "MeterValues response accepted");
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
@@ -270,7 +788,16 @@ fn meter_values_response(rqt: &AfbRequest, args: &AfbRqtData, _ctx: &AfbCtxData)
 
 struct EngyStateRqtCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+}
+// optional connector id argument, default to binding 'cid'
+fn args_cid(args: &AfbRqtData, index: usize, mgr: &ManagerHandle) -> u32 {
+    match args.get::<u32>(index) {
+        Ok(value) => value,
+        Err(_) => mgr.get_cid(),
+    }
 }
+
 // this verb is only for testing purpose real measure should be send from engy event
 // AfbVerbRegister!(EngyStateRqt, engy_state_request, EngyStateRqtCtx);
 fn engy_state_request(
@@ -280,38 +807,33 @@ fn engy_state_request(
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<EngyStateRqtCtx>()?;
     let state = args.get::<&EnergyState>(0)?;
-    let query = engy_event_action(state, ctx.mgr)?;
+    let cid = args_cid(args, 1, ctx.mgr);
+    ctx.mgr.set_meter(cid, state.session)?;
+    ctx.mgr.check_boot()?;
+    if ctx.mgr.get_tid(cid)? == 0 {
+        return afb_error!("ocpp-energy-state", "not active transaction running connector:{}", cid);
+    }
+    let query = ctx.cp.meter_values(ctx.mgr, cid, state)?;
     afb_log_msg!(Debug, rqt, "MeterValues request: {:?}", query);
 
     AfbSubCall::call_async(
         rqt,
         "OCPP-SND",
-        "MeterValues",
-        v106::MeterValues::Request(query),
+        query.action,
+        query.to_jsonc()?,
         meter_values_response,
-        MeterValuesRspCtx {},
+        MeterValuesRspCtx { cp: ctx.cp },
     )?;
 
-    AfbSubCall::call_sync(
-        rqt,
-        "OCPP-SND",
-        "StatusNotification",
-        v106::StatusNotification::Request(v106::StatusNotificationRequest {
-            connector_id: 1,
-            status: v106::ChargePointStatus::Charging,
-            error_code: v106::ChargePointErrorCode::NoError,
-            info: Some("Tux-Evse refresh report".to_string()),
-            timestamp: Some(get_utc()),
-            vendor_id: None,
-            vendor_error_code: None,
-        }),
-    )?;
+    let query = ctx.cp.status_notification(cid, &OcppChargerStatus::Charging)?;
+    AfbSubCall::call_sync(rqt, "OCPP-SND", query.action, query.to_jsonc()?)?;
 
     Ok(())
 }
 
 struct HeartbeatRspCtx {
     nonce: u32,
+    cp: &'static dyn OcppChargePoint,
 }
 // AfbVerbRegister!(HeartbeatRsp, heartbeat_response, HeartbeatRspCtx);
 fn heartbeat_response(
@@ -320,39 +842,39 @@ fn heartbeat_response(
     ctx: &AfbCtxData,
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<HeartbeatRspCtx>()?;
-    let data = args.get::<&v106::Heartbeat>(0)?;
-    let response = match data {
-        v106::Heartbeat::Response(response) => response,
-        _ => return afb_error!("ocpp-heartbeat-response", "invalid response type"),
-    };
+    let current_time = ctx.cp.heartbeat_rsp(args_value(args, 0)?)?;
+    clock_sync(current_time);
 
     afb_log_msg!(
         Debug,
         rqt,
         "Heartbeat response nonce:{} time={}",
         ctx.nonce,
-        response.current_time
+        current_time
     );
     rqt.reply(ctx.nonce, 0);
     Ok(())
 }
-struct HeartbeatRqtCtx { // redundant
-    nonce: u32,
+
+struct HeartbeatRqtCtx {
+    mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
 }
-// Authentication check id_tag on backend
-// AfbVerbRegister!(HeartbeatRqt, heartbeat_request);
-fn heartbeat_request(rqt: &AfbRequest, args: &AfbRqtData, _ctx: &AfbCtxData) -> Result<(), AfbError> {
+// AfbVerbRegister!(HeartbeatRqt, heartbeat_request, HeartbeatRqtCtx);
+fn heartbeat_request(rqt: &AfbRequest, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<HeartbeatRqtCtx>()?;
     let nonce = args.get::<u32>(0)?;
     afb_log_msg!(Debug, rqt, "Heartbeat request nonce:{}", nonce);
+    ctx.mgr.check_boot()?;
 
-    let query = v106::HeartbeatRequest {};
+    let query = ctx.cp.heartbeat()?;
     AfbSubCall::call_async(
         rqt,
         "OCPP-SND",
-        "Heartbeat",
-        v106::Heartbeat::Request(query),
+        query.action,
+        query.to_jsonc()?,
         heartbeat_response,
-        HeartbeatRspCtx { nonce },
+        HeartbeatRspCtx { nonce, cp: ctx.cp },
     )?;
     Ok(())
 }
@@ -360,6 +882,10 @@ fn heartbeat_request(rqt: &AfbRequest, args: &AfbRqtData, _ctx: &AfbCtxData) ->
 // Authorize async start response callback
 struct AuthorizeRspCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    cid: u32,
+    id_tag: String,
 }
 // AfbVerbRegister!(AuthorizeRsp, authorize_response, AuthorizeRspCtx);
 fn authorize_response(
@@ -368,32 +894,46 @@ fn authorize_response(
     ctx: &AfbCtxData,
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<AuthorizeRspCtx>()?;
-    let data = args.get::<&v106::Authorize>(0)?;
-    let response = match data {
-        v106::Authorize::Response(response) => response,
-        _ => return afb_error!("ocpp-authorize-start", "invalid response type"),
+    let ocpp_auth_state = args_value(args, 0).and_then(|payload| {
+        auth_cache_update(ctx.auth, ctx.cp, &ctx.id_tag, &payload);
+        ctx.cp.authorize_rsp(payload)
+    });
+    let ocpp_auth_state = match ocpp_auth_state {
+        Ok(value) => value,
+        Err(error) => return authorize_offline(rqt, ctx.mgr, ctx.auth, ctx.cid, &ctx.id_tag, error),
     };
-
-    let ocpp_auth_state;
-    match response.id_tag_info.status {
-        v106::AuthorizationStatus::Accepted => {
-            afb_log_msg!(Debug, rqt, "ocpp-authorize-done");
-            ocpp_auth_state = true;
-        }
-        _ => {
-            afb_log_msg!(Debug, rqt, "ocpp-authorize-failed");
-            ocpp_auth_state = false;
-        }
-    };
-    ctx.mgr.authorized(ocpp_auth_state)?;
+    if ocpp_auth_state {
+        afb_log_msg!(Debug, rqt, "ocpp-authorize-done");
+    } else {
+        afb_log_msg!(Debug, rqt, "ocpp-authorize-failed");
+    }
+    ctx.mgr.authorized(ctx.cid, ocpp_auth_state)?;
     afb_log_msg!(Notice,None,"OCPP AUTH STATE: {}", ocpp_auth_state);
     rqt.reply(ocpp_auth_state, 0);
     Ok(())
 }
 
+// csms unreachable (boot pending, transport error), decision is taken from local list and cache
+fn authorize_offline(
+    rqt: &AfbRequest,
+    mgr: &ManagerHandle,
+    auth: &OcppAuth,
+    cid: u32,
+    id_tag: &str,
+    error: AfbError,
+) -> Result<(), AfbError> {
+    let authorized = auth.offline_authorize(id_tag)?;
+    afb_log_msg!(Notice, rqt, "Offline authorize tag:{} accepted:{} ({})", id_tag, authorized, error.get_info());
+    mgr.authorized(cid, authorized)?;
+    rqt.reply(authorized, 0);
+    Ok(())
+}
+
 // Authentication check id_tag on backend
 struct AuthorizeRqtCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
 }
 // AfbVerbRegister!(AuthorizeRqt, authorize_request, AuthorizeRqtCtx);
 fn authorize_request(
@@ -403,24 +943,72 @@ fn authorize_request(
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<AuthorizeRqtCtx>()?;
     let id_tag = args.get::<String>(0)?;
+    let cid = args_cid(args, 1, ctx.mgr);
 
-    afb_log_msg!(Debug, rqt, "Authorize request tag:{}", id_tag);
-    let query = v106::AuthorizeRequest { id_tag };
+    afb_log_msg!(Debug, rqt, "Authorize request tag:{} connector:{}", id_tag, cid);
 
-    AfbSubCall::call_async(
+    // LocalPreAuthorize, valid local list or cache entries start without waiting for csms
+    if ctx.auth.is_free_vend() || ctx.auth.local_authorize(&id_tag)? {
+        afb_log_msg!(Debug, rqt, "ocpp-authorize-local tag:{}", id_tag);
+        ctx.mgr.authorized(cid, true)?;
+        rqt.reply(true, 0);
+        return Ok(());
+    }
+
+    if let Err(error) = ctx.mgr.check_boot() {
+        return authorize_offline(rqt, ctx.mgr, ctx.auth, cid, &id_tag, error);
+    }
+    let query = ctx.cp.authorize(&id_tag)?;
+
+    if let Err(error) = AfbSubCall::call_async(
         rqt,
         "OCPP-SND",
-        "Authorize",
-        v106::Authorize::Request(query),
+        query.action,
+        query.to_jsonc()?,
         authorize_response,
-        AuthorizeRspCtx { mgr: ctx.mgr },
-    )?;
+        AuthorizeRspCtx { mgr: ctx.mgr, cp: ctx.cp, auth: ctx.auth, cid, id_tag: id_tag.clone() },
+    ) {
+        return authorize_offline(rqt, ctx.mgr, ctx.auth, cid, &id_tag, error);
+    }
+    Ok(())
+}
+
+// csms unreachable: boot not accepted or earlier transaction messages still waiting for replay
+fn transaction_offline(mgr: &ManagerHandle) -> Result<bool, AfbError> {
+    Ok(mgr.check_boot().is_err() || mgr.offline_pending()?)
+}
+
+// StartTransaction queued for replay, transaction runs with a provisional id until csms returns its own
+fn transaction_start_offline(
+    rqt: &AfbRequest,
+    mgr: &ManagerHandle,
+    cp: &dyn OcppChargePoint,
+    cid: u32,
+    id_tag: &str,
+    query: OcppQuery,
+) -> Result<(), AfbError> {
+    let tid = cp.start_transaction_offline(mgr, cid)?;
+    mgr.offline_push(OcppOfflineTx {
+        cid,
+        tid,
+        id_tag: Some(id_tag.to_string()),
+        start: true,
+        query,
+    })?;
+    mgr.login(cid, tid, id_tag)?;
+    afb_log_msg!(Notice, rqt, "Offline transaction start tid:{} connector:{}", tid, cid);
+    rqt.reply(tid, 0);
     Ok(())
 }
 
 // Transaction async start response callback
 struct TransacStartRspCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    cid: u32,
+    id_tag: String,
+    query: OcppQuery,
 }
 // reference: https://www.ampcontrol.io/ocpp-guide/how-to-start-an-ocpp-charging-session-with-starttransaction
 // AfbVerbRegister!(TransacStartRsp, transac_start_rsp, TransacStartRspCtx);
@@ -430,31 +1018,36 @@ fn transac_start_rsp(
     ctx: &AfbCtxData,
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<TransacStartRspCtx>()?;
-    let data = args.get::<&v106::StartTransaction>(0)?;
-    let response = match data {
-        v106::StartTransaction::Response(response) => response,
-        _ => return afb_error!("ocpp-transaction-start", "invalid response"),
-    };
-
-    let tid = match response.id_tag_info.status {
-        v106::AuthorizationStatus::Accepted => response.transaction_id,
-        _ => {
-            return afb_error!(
-                "ocpp-transaction-start",
-                "fail start:{:?}",
-                response.id_tag_info.status
-            )
+    let payload = match args_response(args, 0) {
+        Ok(value) => value,
+        // csms rejected StartTransaction, transaction does not start
+        Err(error) if is_callerror(&error) => {
+            afb_log_msg!(Warning, rqt, "Transaction Start rejected connector:{} {}", ctx.cid, error.get_info());
+            return Err(error);
+        }
+        Err(error) => {
+            afb_log_msg!(Notice, rqt, "Transaction Start queued {}", error.get_info());
+            return transaction_start_offline(rqt, ctx.mgr, ctx.cp, ctx.cid, &ctx.id_tag, ctx.query.clone());
         }
     };
-    afb_log_msg!(Debug, rqt, "Transaction Start accepted tid:{}", tid);
-    ctx.mgr.login(tid)?;
+    auth_cache_update(ctx.auth, ctx.cp, &ctx.id_tag, &payload);
+    let tid = ctx.cp.start_transaction_rsp(ctx.cid, payload)?;
+    afb_log_msg!(Debug, rqt, "Transaction Start accepted tid:{} connector:{}", tid, ctx.cid);
+    ctx.mgr.login(ctx.cid, tid, &ctx.id_tag)?;
     rqt.reply(tid, 0);
     Ok(())
 }
 
 // Transaction stop async response callback
+#[derive(Clone)]
 pub struct TransacStopRspCtx {
     pub mgr: &'static ManagerHandle,
+    pub cp: &'static dyn OcppChargePoint,
+    pub auth: &'static OcppAuth,
+    pub hook: Option<ResetHook>,
+    pub cid: u32,
+    pub id_tag: Option<String>,
+    pub query: OcppQuery,
 }
 // AfbVerbRegister!(TransacStopRsp, transac_stop_rsp, TransacStopRspCtx);
 fn transac_stop_rsp(
@@ -463,21 +1056,125 @@ fn transac_stop_rsp(
     ctx: &AfbCtxData,
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<TransacStopRspCtx>()?;
-    let data = args.get::<&v106::StopTransaction>(0)?;
-    match data {
-        v106::StopTransaction::Response(response) => response,
-        _ => return afb_error!("ocpp-transaction-stop", "invalid response type"),
+    let payload = match args_response(args, 0) {
+        Ok(value) => value,
+        // csms rejected StopTransaction, transaction keeps running
+        Err(error) if is_callerror(&error) => {
+            afb_log_msg!(Warning, rqt, "Transaction Stop rejected connector:{} {}", ctx.cid, error.get_info());
+            return Err(error);
+        }
+        Err(error) => {
+            afb_log_msg!(Notice, rqt, "Transaction Stop queued {}", error.get_info());
+            return transaction_stop_offline(rqt, ctx);
+        }
     };
+    transaction_stopped(ctx.mgr, ctx.cp, ctx.auth, ctx.cid, ctx.id_tag.as_deref(), payload)?;
 
-    ctx.mgr.logout()?;
     afb_log_msg!(Debug, rqt, "Transaction Stop accepted");
     rqt.reply(AFB_NO_DATA, 0);
+    transaction_stop_closed(rqt, ctx);
+    Ok(())
+}
+
+// already replied, errors are only logged
+fn transaction_stop_closed(rqt: &AfbRequest, stop: &TransacStopRspCtx) {
+    if let Err(error) = transaction_closed(rqt.get_api().get_apiv4(), stop.mgr, stop.cp, stop.hook, stop.cid) {
+        afb_log_msg!(Warning, rqt, "Transaction close connector:{} failed {}", stop.cid, error.get_info());
+    }
+}
+
+// StopTransaction queued for replay behind its StartTransaction, transaction is closed locally
+fn transaction_stop_offline(rqt: &AfbRequest, stop: &TransacStopRspCtx) -> Result<(), AfbError> {
+    let tid = stop.mgr.get_tid(stop.cid)?;
+    stop.mgr.offline_push(OcppOfflineTx {
+        cid: stop.cid,
+        tid,
+        id_tag: stop.id_tag.clone(),
+        start: false,
+        query: stop.query.clone(),
+    })?;
+    afb_log_msg!(Notice, rqt, "Offline transaction stop tid:{} connector:{}", tid, stop.cid);
+    rqt.reply(AFB_NO_DATA, 0);
+    transaction_stop_closed(rqt, stop);
+    Ok(())
+}
+
+fn transaction_stop_send(
+    rqt: &AfbRequest,
+    ctx: &TransacRqtCtx,
+    cid: u32,
+    meter: i32,
+    id_tag: Option<String>,
+) -> Result<(), AfbError> {
+    // reason is Remote when chmgr stops after csms RemoteStopTransaction
+    let reason = ctx.mgr.stop_reason(cid)?;
+    let query = ctx.cp.stop_transaction(ctx.mgr, cid, meter, reason, id_tag.as_deref())?;
+    let stop = TransacStopRspCtx {
+        mgr: ctx.mgr,
+        cp: ctx.cp,
+        auth: ctx.auth,
+        hook: ctx.hook,
+        cid,
+        id_tag,
+        query,
+    };
+    // csms dropped the StartTransaction, transaction is only closed locally
+    if ctx.mgr.offline_dropped(ctx.mgr.get_tid(cid)?)? {
+        afb_log_msg!(Warning, rqt, "Transaction Stop not sent, start dropped connector:{}", cid);
+        rqt.reply(AFB_NO_DATA, 0);
+        transaction_stop_closed(rqt, &stop);
+        return Ok(());
+    }
+    if transaction_offline(ctx.mgr)? {
+        return transaction_stop_offline(rqt, &stop);
+    }
+
+    afb_log_msg!(Debug, rqt, "Transaction Stop request {:?}", &stop.query);
+    let jsonc = stop.query.to_jsonc()?;
+    let action = stop.query.action;
+    let offline = stop.clone();
+    if let Err(error) = AfbSubCall::call_async(rqt, "OCPP-SND", action, jsonc, transac_stop_rsp, stop) {
+        afb_log_msg!(Notice, rqt, "Transaction Stop queued {}", error.get_info());
+        return transaction_stop_offline(rqt, &offline);
+    }
     Ok(())
 }
 
-// Transaction start/stop request
+// Authorize of an unknown tag presented to stop, its parentIdTag should match the start tag one
+struct StopAuthorizeRspCtx {
+    transac: TransacRqtCtx,
+    cid: u32,
+    meter: i32,
+    start_tag: String,
+    id_tag: String,
+}
+// AfbVerbRegister!(StopAuthorizeRsp, stop_authorize_rsp, StopAuthorizeRspCtx);
+fn stop_authorize_rsp(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &AfbCtxData,
+) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<StopAuthorizeRspCtx>()?;
+    let transac = &ctx.transac;
+    let payload = args_value(args, 0)?;
+    auth_cache_update(transac.auth, transac.cp, &ctx.id_tag, &payload);
+    let allowed = match transac.cp.id_tag_info(&payload) {
+        Some(info) => transac.auth.same_parent(&ctx.start_tag, &info)?,
+        None => false,
+    };
+    if !allowed {
+        return afb_error!("ocpp-transaction-stop", "tag:{} not allowed to stop connector:{}", ctx.id_tag, ctx.cid);
+    }
+    transaction_stop_send(rqt, transac, ctx.cid, ctx.meter, Some(ctx.id_tag.clone()))
+}
+
+// Transaction start/stop request, started and stopped locally with csms messages queued while offline
+#[derive(Clone, Copy)]
 struct TransacRqtCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
+    hook: Option<ResetHook>,
 }
 // AfbVerbRegister!(TransacRequest, transaction_request, TransacRqtCtx);
 fn transaction_request(
@@ -487,64 +1184,86 @@ fn transaction_request(
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<TransacRqtCtx>()?;
     let data = args.get::<&OcppTransaction>(0)?;
+    let cid = args_cid(args, 1, ctx.mgr);
     match data {
         OcppTransaction::Start(tag) => {
-            ctx.mgr.check_active_session(false)?;
-            let query = v106::StartTransactionRequest {
-                connector_id: ctx.mgr.get_cid(),
-                id_tag: tag.clone(),
-                meter_start: 0,
-                reservation_id: None,
-                timestamp: get_utc(),
-            };
+            ctx.mgr.check_active_session(cid, false)?;
+            ctx.mgr.check_operative(cid)?;
+            // free vend may replace presented tag
+            let tag = ctx.auth.transaction_tag(tag);
+            let query = ctx.cp.start_transaction(cid, &tag)?;
+            if transaction_offline(ctx.mgr)? {
+                return transaction_start_offline(rqt, ctx.mgr, ctx.cp, cid, &tag, query);
+            }
 
             afb_log_msg!(Debug, rqt, "Transaction Start request: {:?}", &query);
-            AfbSubCall::call_async(
-                rqt,
-                "OCPP-SND",
-                "StartTransaction",
-                v106::StartTransaction::Request(query),
-                transac_start_rsp,
-                TransacStartRspCtx { mgr: ctx.mgr },
-            )?;
-        }
-        OcppTransaction::Stop(meter) => {
-            let tid = ctx.mgr.get_tid()?;
-            ctx.mgr.check_active_session(true)?;
-            let query = v106::StopTransactionRequest {
-                id_tag: None,
-                meter_stop: *meter,
-                timestamp: get_utc(),
-                reason: None,
-                transaction_data: None,
-                transaction_id: tid,
+            let start = TransacStartRspCtx {
+                mgr: ctx.mgr,
+                cp: ctx.cp,
+                auth: ctx.auth,
+                cid,
+                id_tag: tag.clone(),
+                query: query.clone(),
             };
-            afb_log_msg!(Debug, rqt, "Transaction Stop request {:?}", &query);
-            AfbSubCall::call_async(
-                rqt, 
-                "OCPP-SND",
-                "StopTransaction",
-                v106::StopTransaction::Request(query),
-                transac_stop_rsp,
-                TransacStopRspCtx { mgr: ctx.mgr },
-            )?;
+            if let Err(error) =
+                AfbSubCall::call_async(rqt, "OCPP-SND", query.action, query.to_jsonc()?, transac_start_rsp, start)
+            {
+                afb_log_msg!(Notice, rqt, "Transaction Start queued {}", error.get_info());
+                return transaction_start_offline(rqt, ctx.mgr, ctx.cp, cid, &tag, query);
+            }
+        }
+        OcppTransaction::Stop(meter, None) => {
+            ctx.mgr.check_active_session(cid, true)?;
+            transaction_stop_send(rqt, ctx, cid, *meter, None)?;
+        }
+        // presented tag should be the start tag or share its parentIdTag
+        OcppTransaction::Stop(meter, Some(id_tag)) => {
+            ctx.mgr.check_active_session(cid, true)?;
+            let start_tag = ctx.mgr.get_id_tag(cid)?.unwrap_or_default();
+            match ctx.auth.stop_allowed(&start_tag, id_tag)? {
+                Some(true) => {
+                    transaction_stop_send(rqt, ctx, cid, *meter, Some(id_tag.clone()))?;
+                }
+                Some(false) => {
+                    return afb_error!("ocpp-transaction-stop", "tag:{} not allowed to stop connector:{}", id_tag, cid);
+                }
+                // parentIdTag of an unknown tag cannot be checked without csms
+                None if transaction_offline(ctx.mgr)? => {
+                    return afb_error!("ocpp-transaction-stop", "tag:{} unknown while csms is unreachable", id_tag);
+                }
+                None => {
+                    let query = ctx.cp.authorize(id_tag)?;
+                    AfbSubCall::call_async(
+                        rqt,
+                        "OCPP-SND",
+                        query.action,
+                        query.to_jsonc()?,
+                        stop_authorize_rsp,
+                        StopAuthorizeRspCtx {
+                            transac: *ctx,
+                            cid,
+                            meter: *meter,
+                            start_tag,
+                            id_tag: id_tag.clone(),
+                        },
+                    )?;
+                }
+            }
         }
     }
     // reply done from async response
     Ok(())
 }
 
-struct StatusNotificationRspCtx { // redundant
+struct StatusNotificationRspCtx {
+    cp: &'static dyn OcppChargePoint,
 }
 
 // StatusNotification async start response callback
 // AfbVerbRegister!(StatusNotificationRsp, status_notification_rsp);
-fn status_notification_rsp(rqt: &AfbRequest, args: &AfbRqtData, _ctx: &AfbCtxData) -> Result<(), AfbError> {
-    let data = args.get::<&v106::StatusNotification>(0)?;
-    let _response = match data {
-        v106::StatusNotification::Response(response) => response,
-        _ => return afb_error!("ocpp-status-notification", "invalid response type"),
-    };
+fn status_notification_rsp(rqt: &AfbRequest, args: &AfbRqtData, ctx: &AfbCtxData) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<StatusNotificationRspCtx>()?;
+    ctx.cp.status_notification_rsp(args_value(args, 0)?)?;
     rqt.reply(AFB_NO_DATA, 0);
     Ok(())
 }
@@ -552,6 +1271,8 @@ fn status_notification_rsp(rqt: &AfbRequest, args: &AfbRqtData, _ctx: &AfbCtxDat
 // Authentication check id_tag on backend
 struct StatusNotificationRqtCtx {
     mgr: &'static ManagerHandle,
+    cp: &'static dyn OcppChargePoint,
+    auth: &'static OcppAuth,
 }
 // AfbVerbRegister!(StatusNotificationRqt, status_notification_rqt, StatusNotificationRqtCtx);
 fn status_notification_rqt(
@@ -562,18 +1283,53 @@ fn status_notification_rqt(
     let ctx = ctx.get_ref::<StatusNotificationRqtCtx>()?;
     // move from binding to ocpp status
     let status = args.get::<&OcppChargerStatus>(0)?;
-    ctx.mgr.set_status(&status)?;
-    let query = update_charger_status(ctx.mgr, &status)?;
+    let cid = args_cid(args, 1, ctx.mgr);
+    if cid == 0 && !status_connectors(ctx.mgr, ctx.cp).contains(&0) {
+        return afb_error!("ocpp-connector-zero", "connector 0 status not supported by csms/protocol");
+    }
+    let status = ctx.mgr.set_status(cid, &status)?;
+    ctx.mgr.check_boot()?;
+    let query = ctx.cp.status_notification(cid, &status)?;
 
     afb_log_msg!(Debug, rqt, "Status Notification update{:?}", &query);
     AfbSubCall::call_async(
         rqt,
         "OCPP-SND",
-        "StatusNotification",
-        v106::StatusNotification::Request(query),
+        query.action,
+        query.to_jsonc()?,
         status_notification_rsp,
-        StatusNotificationRspCtx {},
+        StatusNotificationRspCtx { cp: ctx.cp },
     )?;
+
+    // EV plugged, start transaction pending from RemoteStartTransaction
+    if let OcppChargerStatus::Preparing = status {
+        remote_start_plugged(rqt.get_api().get_apiv4(), ctx.mgr, ctx.cp, ctx.auth, cid)?;
+    }
+    Ok(())
+}
+
+struct ConfigRqtCtx {
+    store: &'static OcppConfigStore,
+}
+// dump or reset (restore binding defaults) configuration keys
+// AfbVerbRegister!(ConfigRqt, config_request, ConfigRqtCtx);
+fn config_request(
+    rqt: &AfbRequest,
+    args: &AfbRqtData,
+    ctx: &AfbCtxData,
+) -> Result<(), AfbError> {
+    let ctx = ctx.get_ref::<ConfigRqtCtx>()?;
+    let action = args.get::<String>(0)?;
+    match action.to_lowercase().as_str() {
+        "dump" => {}
+        "reset" => {
+            afb_log_msg!(Notice, rqt, "Reset configuration keys path:{:?}", ctx.store.get_path());
+            ctx.store.reset()?;
+        }
+        _ => return afb_error!("ocpp-config-verb", "unsupported action:{} (dump|reset)", action),
+    }
+    let jsonc = JsoncObj::parse(&ctx.store.dump()?.to_string())?;
+    rqt.reply(jsonc, 0);
     Ok(())
 }
 
@@ -589,7 +1345,8 @@ fn subscribe_callback(
 ) -> Result<(), AfbError> {
     let ctx = ctx.get_ref::<SubscribeData>()?;
     let subcription = args.get::<bool>(0)?;
-    ctx.mgr.subscribe(request, subcription)?;
+    let cid = args_cid(args, 1, ctx.mgr);
+    ctx.mgr.subscribe(request, cid, subcription)?;
     request.reply(AFB_NO_DATA, 0);
     Ok(())
 }
@@ -597,36 +1354,41 @@ fn subscribe_callback(
 pub(crate) fn register_frontend(api: &mut AfbApi, config: &BindingConfig) -> Result<(), AfbError> {
     let heartbeat_verb = AfbVerb::new("heartbeat")
         .set_callback(heartbeat_request)
-        .set_context(v106::HeartbeatRequest {})
+        .set_context(HeartbeatRqtCtx { mgr: config.mgr, cp: config.chargepoint })
         .set_info("Request ping backend")
         .finalize()?;
 
     let authorize_verb = AfbVerb::new("authorize")
         .set_callback(authorize_request)
-        .set_context(AuthorizeRqtCtx { mgr: config.mgr })
+        .set_context(AuthorizeRqtCtx { mgr: config.mgr, cp: config.chargepoint, auth: config.auth })
         .set_info("Request tagid authorization from backend")
-        .set_usage("idTag")
+        .set_usage("idTag, [connector]")
         .finalize()?;
 
     let transaction_verb = AfbVerb::new("transaction")
         .set_callback(transaction_request)
-        .set_context(TransacRqtCtx { mgr: config.mgr })
-        .set_info("send start/stop transaction to backend")
-        .set_usage("'idTag'")
+        .set_context(TransacRqtCtx {
+            mgr: config.mgr,
+            cp: config.chargepoint,
+            auth: config.auth,
+            hook: config.reset_hook,
+        })
+        .set_info("send start/stop transaction (1.6) or transaction event (2.0.1) to backend")
+        .set_usage("{'start':'idTag'}|{'stop':[meter, 'idTag'|null]}, [connector]")
         .finalize()?;
 
     let status_notification_verb = AfbVerb::new("status-notification")
         .set_callback(status_notification_rqt)
-        .set_context(StatusNotificationRqtCtx { mgr: config.mgr })
+        .set_context(StatusNotificationRqtCtx { mgr: config.mgr, cp: config.chargepoint, auth: config.auth })
         .set_info("Send status notification to backend")
         .add_sample("'Charging'")?
         .add_sample("'Available'")?
-        .set_usage("ocpp-status")
+        .set_usage("ocpp-status, [connector]")
         .finalize()?;
 
     let engy_state_verb = AfbVerb::new("push-measure")
         .set_callback(engy_state_request)
-        .set_context(EngyStateRqtCtx { mgr: config.mgr })
+        .set_context(EngyStateRqtCtx { mgr: config.mgr, cp: config.chargepoint })
         .set_info("mock engy state event")
         .finalize()?;
 
@@ -634,13 +1396,22 @@ pub(crate) fn register_frontend(api: &mut AfbApi, config: &BindingConfig) -> Res
         .set_callback(subscribe_callback)
         .set_context(SubscribeData { mgr: config.mgr })
         .set_info("subscribe auth-msg event")
-        .set_usage("true|false")
+        .set_usage("true|false, [connector]")
+        .finalize()?;
+
+    let config_verb = AfbVerb::new("config")
+        .set_callback(config_request)
+        .set_context(ConfigRqtCtx { store: config.store })
+        .set_info("dump or reset ocpp configuration keys")
+        .add_sample("'dump'")?
+        .add_sample("'reset'")?
+        .set_usage("dump|reset")
         .finalize()?;
 
     let monitor_handler = AfbEvtHandler::new("monitor-evt")
         .set_pattern("monitor/disconnected")
         .set_callback(monitor_event_cb)
-        .set_context(MonitorEvtCtx { mgr: config.mgr })
+        .set_context(MonitorEvtCtx { mgr: config.mgr, cp: config.chargepoint })
         .finalize()?;
 
     // register veb within API
@@ -650,6 +1421,7 @@ pub(crate) fn register_frontend(api: &mut AfbApi, config: &BindingConfig) -> Res
     api.add_verb(engy_state_verb);
     api.add_verb(heartbeat_verb);
     api.add_verb(subscribe_verb);
+    api.add_verb(config_verb);
     api.add_evt_handler(monitor_handler);
 
     Ok(())
@@ -89,7 +89,7 @@ impl AfbApiControls for TapUserData {
         let stop_transaction = AfbTapTest::new("transaction-stop", self.target, "transaction")
             .set_info("send stop transaction")
             .set_delay(10000) // wait 10s before pushing this test
-            .add_arg(OcppTransaction::Stop(energy_state.session))?
+            .add_arg(OcppTransaction::Stop(energy_state.session, None))?
             .finalize()?;
 
         let stopped_charge =
@@ -0,0 +1,111 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ */
+
+// Minimal afbv4 replacement used when crates are compiled without 'afbv4' feature
+// (standalone tools, integration tests). Only the subset used by types/ocpp-lib is provided.
+
+use serde::Serialize;
+use std::fmt;
+use std::sync::Mutex;
+
+pub use crate::{afb_error, AfbDataConverter};
+
+#[derive(Debug, Clone)]
+pub struct AfbError {
+    uid: String,
+    info: String,
+}
+
+impl AfbError {
+    pub fn new(uid: &str, info: String) -> Self {
+        AfbError {
+            uid: uid.to_string(),
+            info,
+        }
+    }
+
+    pub fn get_uid(&self) -> String {
+        self.uid.clone()
+    }
+
+    pub fn get_info(&self) -> String {
+        self.info.clone()
+    }
+}
+
+impl fmt::Display for AfbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "uid:{} info:{}", self.uid, self.info)
+    }
+}
+
+impl std::error::Error for AfbError {}
+
+#[macro_export]
+macro_rules! afb_error {
+    ($uid:expr, $format:expr $(, $args:expr)* $(,)?) => {
+        Err($crate::compat::AfbError::new($uid, format!($format $(, $args)*)))
+    };
+}
+
+// without afb-binder there is no data converter to register
+#[macro_export]
+macro_rules! AfbDataConverter {
+    ($module:ident, $datatype:ty) => {
+        pub mod $module {
+            pub fn register() -> Result<(), $crate::compat::AfbError> {
+                Ok(())
+            }
+        }
+    };
+}
+
+// event messages are serialized to json and forwarded to an optional handler
+pub type AfbEventHandler = Box<dyn Fn(&str, String) + Send>;
+
+pub struct AfbEvent {
+    uid: &'static str,
+    handler: Mutex<Option<AfbEventHandler>>,
+}
+
+impl AfbEvent {
+    pub fn new(uid: &'static str) -> &'static Self {
+        let event = AfbEvent {
+            uid,
+            handler: Mutex::new(None),
+        };
+        Box::leak(Box::new(event))
+    }
+
+    pub fn set_handler(&self, handler: AfbEventHandler) -> &Self {
+        *self.handler.lock().unwrap() = Some(handler);
+        self
+    }
+
+    pub fn get_uid(&self) -> &'static str {
+        self.uid
+    }
+
+    pub fn push<T>(&self, data: T) -> i32
+    where
+        T: Serialize,
+    {
+        let handler = self.handler.lock().unwrap();
+        match (handler.as_ref(), serde_json::to_string(&data)) {
+            (Some(callback), Ok(json)) => {
+                callback(self.uid, json);
+                1
+            }
+            _ => 0,
+        }
+    }
+}
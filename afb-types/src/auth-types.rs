@@ -10,7 +10,10 @@
  *
  */
 
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use crate::compat::*;
 use serde::{Deserialize, Serialize};
 
 AfbDataConverter!(auth_msg, AuthMsg);
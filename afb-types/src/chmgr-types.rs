@@ -11,7 +11,10 @@
  */
 
 use crate::prelude::*;
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use crate::compat::*;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -9,7 +9,10 @@
  *   http://www.apache.org/licenses/LICENSE-2.0
  *
  */
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use crate::compat::*;
 use serde::{Deserialize, Serialize};
 use  std::time::Duration;
 
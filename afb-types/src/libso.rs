@@ -15,9 +15,13 @@
     html_favicon_url = "https://iot.bzh/images/defaults/favicon.ico"
 )]
 
-#[cfg(not(afbv4))]
+#[cfg(feature = "afbv4")]
 extern crate afbv4;
 
+#[cfg(not(feature = "afbv4"))]
+#[path = "afb-compat.rs"]
+pub mod compat;
+
 #[path = "chmgr-types.rs"]
 mod chmgr;
 
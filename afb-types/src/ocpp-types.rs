@@ -10,7 +10,10 @@
  *
  */
 
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use crate::compat::*;
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +27,8 @@ pub enum OcppMsg {
     Transaction(bool,u32),
     Reservation(ReservationSession),
     PowerLimit(PowerLimit),
+    Availability(bool),
+    RemoteStart(String),
     Unknown,
 }
 
@@ -32,7 +37,27 @@ AfbDataConverter!(ocpp_transaction, OcppTransaction);
 #[serde(rename_all = "lowercase")]
 pub enum OcppTransaction {
     Start(String),
-    Stop(i32),
+    // final meter, tag presented to stop (None when charge point stops)
+    // bare {'stop':meter} from older chmgr is still accepted
+    #[serde(deserialize_with = "stop_deserialize")]
+    Stop(i32, Option<String>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OcppStopArgs {
+    Meter(i32),
+    Tagged(i32, Option<String>),
+}
+
+fn stop_deserialize<'de, D>(deserializer: D) -> Result<(i32, Option<String>), D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match OcppStopArgs::deserialize(deserializer)? {
+        OcppStopArgs::Meter(meter) => Ok((meter, None)),
+        OcppStopArgs::Tagged(meter, id_tag) => Ok((meter, id_tag)),
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -100,3 +125,49 @@ pub fn ocpp_registers() -> Result<(), AfbError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(json: &str) -> OcppTransaction {
+        serde_json::from_str::<OcppTransaction>(json).unwrap()
+    }
+
+    #[test]
+    fn stop_bare_meter() {
+        match decode(r#"{"stop":1234}"#) {
+            OcppTransaction::Stop(meter, id_tag) => {
+                assert_eq!(meter, 1234);
+                assert_eq!(id_tag, None);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stop_with_tag() {
+        match decode(r#"{"stop":[1234,"tag-01"]}"#) {
+            OcppTransaction::Stop(meter, id_tag) => {
+                assert_eq!(meter, 1234);
+                assert_eq!(id_tag.as_deref(), Some("tag-01"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match decode(r#"{"stop":[1234,null]}"#) {
+            OcppTransaction::Stop(meter, id_tag) => {
+                assert_eq!(meter, 1234);
+                assert_eq!(id_tag, None);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stop_roundtrip() {
+        let json = serde_json::to_string(&OcppTransaction::Stop(10, Some("tag".to_string()))).unwrap();
+        assert_eq!(json, r#"{"stop":[10,"tag"]}"#);
+        assert!(matches!(decode(&json), OcppTransaction::Stop(10, Some(_))));
+        assert!(serde_json::from_str::<OcppTransaction>(r#"{"stop":"1234"}"#).is_err());
+    }
+}
@@ -10,7 +10,10 @@
  *
  */
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use crate::compat::*;
 
 AfbDataConverter!(slac_status, SlacStatus);
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
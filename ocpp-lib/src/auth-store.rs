@@ -0,0 +1,624 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Reference: OCPP-1.6 §3.5 Local Authorization List & Authorization Cache
+ */
+
+// Local id tag authorization, entries are persisted in config store private section
+// and checked before contacting the csms. Local list has priority over the cache.
+
+use crate::prelude::*;
+#[cfg(feature = "afbv4")]
+use afbv4::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+
+// store private entries holding local authorization list and cache
+const PRIVATE_LOCAL_LIST: &str = "local_list";
+const PRIVATE_AUTH_CACHE: &str = "auth_cache";
+
+// least recently used tags are evicted beyond this size
+pub const AUTH_CACHE_MAX_LENGTH: usize = 100;
+
+struct OcppLocalList {
+    // 0 means empty list
+    version: i32,
+    entries: HashMap<String, v106::IdTagInfo>,
+}
+
+// idTagInfo received from csms, most recently used last
+#[derive(Clone, Serialize, Deserialize)]
+struct OcppCacheEntry {
+    id_tag: String,
+    info: v106::IdTagInfo,
+}
+
+// free vend accepts every tag without csms Authorize, IdTag replaces presented tag in StartTransaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OcppFreeVend {
+    Disabled,
+    AnyTag,
+    IdTag(&'static str),
+}
+
+pub struct OcppAuth {
+    local_list: Mutex<OcppLocalList>,
+    cache: Mutex<Vec<OcppCacheEntry>>,
+    // tags accepted while csms was unreachable, checked with Authorize once back online
+    offline: Mutex<Vec<String>>,
+    free_vend: OcppFreeVend,
+    store: &'static OcppConfigStore,
+}
+
+impl OcppAuth {
+    // restore persisted local list, invalid content is dropped
+    pub fn new(store: &'static OcppConfigStore, free_vend: OcppFreeVend) -> &'static Self {
+        let mut local_list = OcppLocalList {
+            version: 0,
+            entries: HashMap::new(),
+        };
+        if let Some(value) = store.get_private(PRIVATE_LOCAL_LIST) {
+            let entries = value.get("entries").and_then(|value| {
+                serde_json::from_value::<HashMap<String, v106::IdTagInfo>>(value.clone()).ok()
+            });
+            let version = value.get("version").and_then(|value| value.as_i64());
+            if let (Some(entries), Some(version)) = (entries, version) {
+                local_list.version = version as i32;
+                local_list.entries = entries;
+            }
+        }
+
+        let cache = match store.get_private(PRIVATE_AUTH_CACHE) {
+            Some(value) => serde_json::from_value::<Vec<OcppCacheEntry>>(value).unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let handle = OcppAuth {
+            local_list: Mutex::new(local_list),
+            cache: Mutex::new(cache),
+            offline: Mutex::new(Vec::new()),
+            free_vend,
+            store,
+        };
+        Box::leak(Box::new(handle))
+    }
+
+    #[track_caller]
+    fn get_local_list(&self) -> Result<MutexGuard<'_, OcppLocalList>, AfbError> {
+        match self.local_list.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-auth-store", "fail to lock local list"),
+        }
+    }
+
+    #[track_caller]
+    fn get_cache(&self) -> Result<MutexGuard<'_, Vec<OcppCacheEntry>>, AfbError> {
+        match self.cache.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-auth-store", "fail to lock authorization cache"),
+        }
+    }
+
+    #[track_caller]
+    fn get_offline(&self) -> Result<MutexGuard<'_, Vec<String>>, AfbError> {
+        match self.offline.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-auth-store", "fail to lock offline tags"),
+        }
+    }
+
+    fn save_cache(&self, cache: &[OcppCacheEntry]) -> Result<(), AfbError> {
+        match serde_json::to_value(cache) {
+            Ok(value) => self.store.set_private(PRIVATE_AUTH_CACHE, value),
+            Err(error) => afb_error!("ocpp-auth-store", "authorization cache encoding {}", error),
+        }
+    }
+
+    fn save_local_list(&self, local_list: &OcppLocalList) -> Result<(), AfbError> {
+        let entries = match serde_json::to_value(&local_list.entries) {
+            Ok(value) => value,
+            Err(error) => return afb_error!("ocpp-auth-store", "local list encoding {}", error),
+        };
+        self.store.set_private(
+            PRIVATE_LOCAL_LIST,
+            json!({"version": local_list.version, "entries": entries}),
+        )
+    }
+
+    // -1 tells csms local list is not supported
+    pub fn get_list_version(&self) -> Result<i32, AfbError> {
+        if !self.store.get_bool(CFG_LOCAL_AUTH_LIST_ENABLED)? {
+            return Ok(-1);
+        }
+        Ok(self.get_local_list()?.version)
+    }
+
+    // Full replaces the list, Differential updates entries (no idTagInfo removes it) and requires a newer version
+    // list is left untouched when update fails
+    pub fn send_local_list(
+        &self,
+        request: &v106::SendLocalListRequest,
+    ) -> Result<v106::UpdateStatus, AfbError> {
+        if !self.store.get_bool(CFG_LOCAL_AUTH_LIST_ENABLED)? {
+            return Ok(v106::UpdateStatus::NotSupported);
+        }
+
+        let updates = match &request.local_authorization_list {
+            Some(value) => value.as_slice(),
+            None => &[],
+        };
+        if updates.len() > self.store.get_u32(CFG_SEND_LOCAL_LIST_MAX_LENGTH)? as usize {
+            return Ok(v106::UpdateStatus::Failed);
+        }
+
+        let mut local_list = self.get_local_list()?;
+        let mut entries = match request.update_type {
+            v106::UpdateType::Full => HashMap::new(),
+            v106::UpdateType::Differential => {
+                if request.list_version <= local_list.version {
+                    return Ok(v106::UpdateStatus::VersionMismatch);
+                }
+                local_list.entries.clone()
+            }
+        };
+
+        for update in updates {
+            match &update.id_tag_info {
+                Some(info) => {
+                    entries.insert(update.id_tag.clone(), info.clone());
+                }
+                // removing an entry is meaningless within a full update
+                None if request.update_type == v106::UpdateType::Full => {
+                    return Ok(v106::UpdateStatus::Failed);
+                }
+                None => {
+                    entries.remove(&update.id_tag);
+                }
+            }
+        }
+        if entries.len() > self.store.get_u32(CFG_LOCAL_AUTH_LIST_MAX_LENGTH)? as usize {
+            return Ok(v106::UpdateStatus::Failed);
+        }
+
+        let update = OcppLocalList {
+            version: request.list_version,
+            entries,
+        };
+        // csms should resend a list that was not persisted
+        if self.save_local_list(&update).is_err() {
+            return Ok(v106::UpdateStatus::Failed);
+        }
+        *local_list = update;
+        Ok(v106::UpdateStatus::Accepted)
+    }
+
+    // local list entry, None when list is disabled or tag is unknown
+    pub fn get_local(&self, id_tag: &str) -> Result<Option<v106::IdTagInfo>, AfbError> {
+        if !self.store.get_bool(CFG_LOCAL_AUTH_LIST_ENABLED)? {
+            return Ok(None);
+        }
+        Ok(self.get_local_list()?.entries.get(id_tag).cloned())
+    }
+
+    // idTagInfo from Authorize, StartTransaction or StopTransaction responses, local list tags are not cached
+    pub fn cache_update(&self, id_tag: &str, info: v106::IdTagInfo) -> Result<(), AfbError> {
+        if !self.store.get_bool(CFG_AUTHORIZATION_CACHE_ENABLED)? || self.get_local(id_tag)?.is_some() {
+            return Ok(());
+        }
+        let mut cache = self.get_cache()?;
+        cache.retain(|entry| entry.id_tag != id_tag);
+        cache.push(OcppCacheEntry {
+            id_tag: id_tag.to_string(),
+            info,
+        });
+        if cache.len() > AUTH_CACHE_MAX_LENGTH {
+            let count = cache.len() - AUTH_CACHE_MAX_LENGTH;
+            cache.drain(0..count);
+        }
+        self.save_cache(&cache)
+    }
+
+    // cached entry, expired entries are marked Expired
+    pub fn get_cached(&self, id_tag: &str) -> Result<Option<v106::IdTagInfo>, AfbError> {
+        if !self.store.get_bool(CFG_AUTHORIZATION_CACHE_ENABLED)? {
+            return Ok(None);
+        }
+        let mut cache = self.get_cache()?;
+        let index = match cache.iter().position(|entry| entry.id_tag == id_tag) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let mut entry = cache.remove(index);
+        if entry.info.status == v106::AuthorizationStatus::Accepted && !Self::is_valid(&entry.info) {
+            entry.info.status = v106::AuthorizationStatus::Expired;
+        }
+        let info = entry.info.clone();
+        cache.push(entry);
+        Ok(Some(info))
+    }
+
+    // ClearCache.req, cache is emptied even when disabled
+    pub fn clear_cache(&self) -> Result<(), AfbError> {
+        let mut cache = self.get_cache()?;
+        cache.clear();
+        self.save_cache(&cache)
+    }
+
+    // known idTagInfo, local list has priority over cache
+    pub fn get_info(&self, id_tag: &str) -> Result<Option<v106::IdTagInfo>, AfbError> {
+        match self.get_local(id_tag)? {
+            Some(value) => Ok(Some(value)),
+            None => self.get_cached(id_tag),
+        }
+    }
+
+    // LocalPreAuthorize, true when tag may start without csms Authorize
+    pub fn local_authorize(&self, id_tag: &str) -> Result<bool, AfbError> {
+        if !self.store.get_bool(CFG_LOCAL_PRE_AUTHORIZE)? {
+            return Ok(false);
+        }
+        match self.get_info(id_tag)? {
+            Some(info) => Ok(Self::is_valid(&info)),
+            None => Ok(false),
+        }
+    }
+
+    pub fn is_free_vend(&self) -> bool {
+        self.free_vend != OcppFreeVend::Disabled
+    }
+
+    // idTag sent with StartTransaction
+    pub fn transaction_tag(&self, id_tag: &str) -> String {
+        match self.free_vend {
+            OcppFreeVend::IdTag(value) => value.to_string(),
+            _ => id_tag.to_string(),
+        }
+    }
+
+    // csms unreachable, known tags follow LocalAuthorizeOffline, unknown ones AllowOfflineTxForUnknownId
+    pub fn offline_authorize(&self, id_tag: &str) -> Result<bool, AfbError> {
+        let authorized = match self.get_info(id_tag)? {
+            Some(info) => self.store.get_bool(CFG_LOCAL_AUTHORIZE_OFFLINE)? && Self::is_valid(&info),
+            None => self.store.get_bool(CFG_ALLOW_OFFLINE_TX_FOR_UNKNOWN_ID)?,
+        };
+        if authorized {
+            self.set_offline(id_tag)?;
+        }
+        Ok(authorized)
+    }
+
+    pub fn set_offline(&self, id_tag: &str) -> Result<(), AfbError> {
+        let mut offline = self.get_offline()?;
+        if !offline.iter().any(|tag| tag == id_tag) {
+            offline.push(id_tag.to_string());
+        }
+        Ok(())
+    }
+
+    // tags to reconcile with csms, list is emptied
+    pub fn take_offline(&self) -> Result<Vec<String>, AfbError> {
+        Ok(std::mem::take(&mut *self.get_offline()?))
+    }
+
+    // a tag may stop a transaction started by another one sharing the same parentIdTag
+    // None when presented tag is unknown and csms Authorize should provide its parent
+    pub fn stop_allowed(&self, start_tag: &str, id_tag: &str) -> Result<Option<bool>, AfbError> {
+        if start_tag == id_tag {
+            return Ok(Some(true));
+        }
+        match self.get_info(id_tag)? {
+            Some(info) => Ok(Some(self.same_parent(start_tag, &info)?)),
+            None => Ok(None),
+        }
+    }
+
+    // start tag parent comes from local list or cache (StartTransaction idTagInfo)
+    pub fn same_parent(&self, start_tag: &str, info: &v106::IdTagInfo) -> Result<bool, AfbError> {
+        let start_parent = match self.get_info(start_tag)? {
+            Some(value) => value.parent_id_tag,
+            None => None,
+        };
+        match (start_parent, &info.parent_id_tag) {
+            (Some(start_parent), Some(parent)) => Ok(&start_parent == parent),
+            _ => Ok(false),
+        }
+    }
+
+    // Accepted and not expired
+    pub fn is_valid(info: &v106::IdTagInfo) -> bool {
+        let expired = match info.expiry_date {
+            Some(date) => date < get_utc(),
+            None => false,
+        };
+        info.status == v106::AuthorizationStatus::Accepted && !expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    // in-memory store, nothing is written to disk
+    fn auth_new() -> &'static OcppAuth {
+        let store = OcppConfigStore::new(v106_core_keys(60, 1), None);
+        OcppAuth::new(store, OcppFreeVend::Disabled)
+    }
+
+    fn local_list(version: i32, update: &str, entries: Value) -> v106::SendLocalListRequest {
+        serde_json::from_value(json!({
+            "listVersion": version,
+            "updateType": update,
+            "localAuthorizationList": entries,
+        }))
+        .unwrap()
+    }
+
+    fn local_status(auth: &OcppAuth, id_tag: &str) -> Option<v106::AuthorizationStatus> {
+        auth.get_local(id_tag).unwrap().map(|info| info.status)
+    }
+
+    #[test]
+    fn local_list_full() {
+        let auth = auth_new();
+        assert_eq!(auth.get_list_version().unwrap(), 0);
+
+        let request = local_list(
+            1,
+            "Full",
+            json!([
+                {"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}},
+                {"idTag": "tag-b", "idTagInfo": {"status": "Blocked"}},
+            ]),
+        );
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::Accepted));
+        assert_eq!(auth.get_list_version().unwrap(), 1);
+        assert_eq!(local_status(auth, "tag-a"), Some(v106::AuthorizationStatus::Accepted));
+        assert_eq!(local_status(auth, "tag-b"), Some(v106::AuthorizationStatus::Blocked));
+        assert_eq!(local_status(auth, "tag-c"), None);
+
+        // full update replaces previous content
+        let request = local_list(2, "Full", json!([{"idTag": "tag-c", "idTagInfo": {"status": "Accepted"}}]));
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::Accepted));
+        assert_eq!(local_status(auth, "tag-a"), None);
+        assert_eq!(local_status(auth, "tag-c"), Some(v106::AuthorizationStatus::Accepted));
+    }
+
+    #[test]
+    fn local_list_differential() {
+        let auth = auth_new();
+        let request = local_list(
+            1,
+            "Full",
+            json!([
+                {"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}},
+                {"idTag": "tag-b", "idTagInfo": {"status": "Accepted"}},
+            ]),
+        );
+        auth.send_local_list(&request).unwrap();
+
+        // entry without idTagInfo is removed
+        let request = local_list(
+            2,
+            "Differential",
+            json!([
+                {"idTag": "tag-a"},
+                {"idTag": "tag-b", "idTagInfo": {"status": "Blocked"}},
+                {"idTag": "tag-c", "idTagInfo": {"status": "Accepted"}},
+            ]),
+        );
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::Accepted));
+        assert_eq!(auth.get_list_version().unwrap(), 2);
+        assert_eq!(local_status(auth, "tag-a"), None);
+        assert_eq!(local_status(auth, "tag-b"), Some(v106::AuthorizationStatus::Blocked));
+        assert_eq!(local_status(auth, "tag-c"), Some(v106::AuthorizationStatus::Accepted));
+    }
+
+    #[test]
+    fn local_list_version_mismatch() {
+        let auth = auth_new();
+        let request = local_list(5, "Full", json!([{"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}}]));
+        auth.send_local_list(&request).unwrap();
+
+        for version in [4, 5] {
+            let request = local_list(version, "Differential", json!([{"idTag": "tag-a"}]));
+            let status = auth.send_local_list(&request).unwrap();
+            assert!(matches!(status, v106::UpdateStatus::VersionMismatch));
+        }
+        assert_eq!(auth.get_list_version().unwrap(), 5);
+        assert_eq!(local_status(auth, "tag-a"), Some(v106::AuthorizationStatus::Accepted));
+    }
+
+    #[test]
+    fn local_list_failed() {
+        let auth = auth_new();
+        let request = local_list(1, "Full", json!([{"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}}]));
+        auth.send_local_list(&request).unwrap();
+
+        // removal within a full update
+        let request = local_list(2, "Full", json!([{"idTag": "tag-a"}]));
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::Failed));
+
+        // resulting list larger than LocalAuthListMaxLength
+        auth.store.set_value(CFG_LOCAL_AUTH_LIST_MAX_LENGTH, "2").unwrap();
+        let request = local_list(
+            3,
+            "Differential",
+            json!([
+                {"idTag": "tag-b", "idTagInfo": {"status": "Accepted"}},
+                {"idTag": "tag-c", "idTagInfo": {"status": "Accepted"}},
+            ]),
+        );
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::Failed));
+
+        // update larger than SendLocalListMaxLength
+        auth.store.set_value(CFG_SEND_LOCAL_LIST_MAX_LENGTH, "1").unwrap();
+        let request = local_list(
+            4,
+            "Full",
+            json!([
+                {"idTag": "tag-b", "idTagInfo": {"status": "Accepted"}},
+                {"idTag": "tag-c", "idTagInfo": {"status": "Accepted"}},
+            ]),
+        );
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::Failed));
+
+        // list is left untouched
+        assert_eq!(auth.get_list_version().unwrap(), 1);
+        assert_eq!(local_status(auth, "tag-a"), Some(v106::AuthorizationStatus::Accepted));
+        assert_eq!(local_status(auth, "tag-b"), None);
+    }
+
+    #[test]
+    fn local_list_disabled() {
+        let auth = auth_new();
+        let request = local_list(1, "Full", json!([{"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}}]));
+        auth.send_local_list(&request).unwrap();
+
+        auth.store.set_value(CFG_LOCAL_AUTH_LIST_ENABLED, "false").unwrap();
+        let status = auth.send_local_list(&request).unwrap();
+        assert!(matches!(status, v106::UpdateStatus::NotSupported));
+        assert_eq!(auth.get_list_version().unwrap(), -1);
+        assert_eq!(local_status(auth, "tag-a"), None);
+    }
+
+    #[test]
+    fn local_authorize() {
+        let auth = auth_new();
+        let expired = get_utc() - chrono::Duration::hours(1);
+        let request = local_list(
+            1,
+            "Full",
+            json!([
+                {"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}},
+                {"idTag": "tag-b", "idTagInfo": {"status": "Blocked"}},
+                {"idTag": "tag-c", "idTagInfo": {"status": "Accepted", "expiryDate": expired}},
+            ]),
+        );
+        auth.send_local_list(&request).unwrap();
+
+        // LocalPreAuthorize is disabled by default
+        assert!(!auth.local_authorize("tag-a").unwrap());
+
+        auth.store.set_value(CFG_LOCAL_PRE_AUTHORIZE, "true").unwrap();
+        assert!(auth.local_authorize("tag-a").unwrap());
+        assert!(!auth.local_authorize("tag-b").unwrap());
+        assert!(!auth.local_authorize("tag-c").unwrap());
+        assert!(!auth.local_authorize("tag-unknown").unwrap());
+    }
+
+    fn tag_info(status: v106::AuthorizationStatus) -> v106::IdTagInfo {
+        v106::IdTagInfo {
+            expiry_date: None,
+            parent_id_tag: None,
+            status,
+        }
+    }
+
+    fn cached_status(auth: &OcppAuth, id_tag: &str) -> Option<v106::AuthorizationStatus> {
+        auth.get_cached(id_tag).unwrap().map(|info| info.status)
+    }
+
+    #[test]
+    fn cache_lru_eviction() {
+        let auth = auth_new();
+        for index in 0..AUTH_CACHE_MAX_LENGTH {
+            let id_tag = format!("tag-{}", index);
+            auth.cache_update(&id_tag, tag_info(v106::AuthorizationStatus::Accepted)).unwrap();
+        }
+
+        // lookup makes tag-0 most recently used, tag-1 becomes the oldest one
+        assert_eq!(cached_status(auth, "tag-0"), Some(v106::AuthorizationStatus::Accepted));
+        auth.cache_update("tag-new", tag_info(v106::AuthorizationStatus::Accepted)).unwrap();
+
+        assert_eq!(auth.get_cache().unwrap().len(), AUTH_CACHE_MAX_LENGTH);
+        assert_eq!(cached_status(auth, "tag-1"), None);
+        assert_eq!(cached_status(auth, "tag-0"), Some(v106::AuthorizationStatus::Accepted));
+        assert_eq!(cached_status(auth, "tag-2"), Some(v106::AuthorizationStatus::Accepted));
+        assert_eq!(cached_status(auth, "tag-new"), Some(v106::AuthorizationStatus::Accepted));
+
+        // update of a cached tag does not grow the cache
+        auth.cache_update("tag-2", tag_info(v106::AuthorizationStatus::Blocked)).unwrap();
+        assert_eq!(auth.get_cache().unwrap().len(), AUTH_CACHE_MAX_LENGTH);
+        assert_eq!(cached_status(auth, "tag-2"), Some(v106::AuthorizationStatus::Blocked));
+    }
+
+    #[test]
+    fn cache_expiry() {
+        let auth = auth_new();
+        let mut info = tag_info(v106::AuthorizationStatus::Accepted);
+        info.expiry_date = Some(get_utc() - chrono::Duration::hours(1));
+        auth.cache_update("tag-expired", info).unwrap();
+        let mut info = tag_info(v106::AuthorizationStatus::Accepted);
+        info.expiry_date = Some(get_utc() + chrono::Duration::hours(1));
+        auth.cache_update("tag-valid", info).unwrap();
+
+        assert_eq!(cached_status(auth, "tag-expired"), Some(v106::AuthorizationStatus::Expired));
+        assert_eq!(cached_status(auth, "tag-valid"), Some(v106::AuthorizationStatus::Accepted));
+
+        auth.store.set_value(CFG_LOCAL_PRE_AUTHORIZE, "true").unwrap();
+        assert!(!auth.local_authorize("tag-expired").unwrap());
+        assert!(auth.local_authorize("tag-valid").unwrap());
+    }
+
+    #[test]
+    fn cache_skip_local_list() {
+        let auth = auth_new();
+        let request = local_list(1, "Full", json!([{"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}}]));
+        auth.send_local_list(&request).unwrap();
+
+        auth.cache_update("tag-a", tag_info(v106::AuthorizationStatus::Blocked)).unwrap();
+        assert_eq!(cached_status(auth, "tag-a"), None);
+
+        auth.store.set_value(CFG_AUTHORIZATION_CACHE_ENABLED, "false").unwrap();
+        auth.cache_update("tag-b", tag_info(v106::AuthorizationStatus::Accepted)).unwrap();
+        auth.store.set_value(CFG_AUTHORIZATION_CACHE_ENABLED, "true").unwrap();
+        assert_eq!(cached_status(auth, "tag-b"), None);
+    }
+
+    #[test]
+    fn cache_reload() {
+        let path = std::env::temp_dir().join(format!("ocpp-auth-test-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let store = OcppConfigStore::new(v106_core_keys(60, 1), Some(path));
+        let auth = OcppAuth::new(store, OcppFreeVend::Disabled);
+        let request = local_list(3, "Full", json!([{"idTag": "tag-a", "idTagInfo": {"status": "Accepted"}}]));
+        auth.send_local_list(&request).unwrap();
+        auth.cache_update("tag-b", tag_info(v106::AuthorizationStatus::Accepted)).unwrap();
+        auth.cache_update("tag-c", tag_info(v106::AuthorizationStatus::Blocked)).unwrap();
+
+        // new store reads back the file, as after a reboot
+        let store = OcppConfigStore::new(v106_core_keys(60, 1), Some(path));
+        store.load().unwrap();
+        let auth = OcppAuth::new(store, OcppFreeVend::Disabled);
+        assert_eq!(auth.get_list_version().unwrap(), 3);
+        assert_eq!(local_status(auth, "tag-a"), Some(v106::AuthorizationStatus::Accepted));
+        assert_eq!(cached_status(auth, "tag-b"), Some(v106::AuthorizationStatus::Accepted));
+        assert_eq!(cached_status(auth, "tag-c"), Some(v106::AuthorizationStatus::Blocked));
+
+        // cleared cache is persisted too
+        auth.clear_cache().unwrap();
+        let auth = OcppAuth::new(auth.store, OcppFreeVend::Disabled);
+        assert_eq!(cached_status(auth, "tag-b"), None);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,843 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Reference:
+ *   OCPP-1.6 §4 operations initiated by charge point
+ *   OCPP-2.0.1 part2 B,C,E,G,J functional blocks
+ */
+
+// Protocol neutral charge point, frontend verbs build their messages through this trait
+// and never depend on v106/v201 structures. Implementation is selected from binding config.
+
+use crate::prelude::*;
+#[cfg(feature = "afbv4")]
+use afbv4::prelude::*;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+use typesv4::prelude::*;
+
+// outgoing charge point message, ready to be sent through OCPP-SND or OcppRpc
+#[derive(Debug, Clone)]
+pub struct OcppQuery {
+    pub action: &'static str,
+    pub payload: Value,
+    // clock offset used for timestamps, detects queries built before csms clock sync
+    clock: i64,
+}
+
+impl OcppQuery {
+    pub fn new<T: OcppAction>(data: T) -> Result<Self, AfbError> {
+        match serde_json::to_value(data) {
+            Ok(payload) => Ok(OcppQuery {
+                action: T::ACTION,
+                payload,
+                clock: clock_offset(),
+            }),
+            Err(error) => afb_error!("ocpp-query-encode", "action:{} {}", T::ACTION, error),
+        }
+    }
+
+    // payload re-stamped with current clock offset
+    pub fn get_payload(&self) -> Value {
+        let mut payload = self.payload.clone();
+        let delta = clock_offset() - self.clock;
+        if delta != 0 {
+            shift_timestamps(&mut payload, delta);
+        }
+        payload
+    }
+
+    #[cfg(feature = "afbv4")]
+    pub fn to_jsonc(&self) -> Result<JsoncObj, AfbError> {
+        JsoncObj::parse(&self.get_payload().to_string())
+    }
+}
+
+// version neutral BootNotification response
+#[derive(Debug, Clone)]
+pub struct OcppBootResponse {
+    pub status: OcppBootStatus,
+    pub interval: u32,
+    pub current_time: DateTime<Utc>,
+}
+
+// version neutral StopTransaction reason, Local is the default (1.6 reason omitted)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OcppStopReason {
+    Local,
+    Remote,
+    HardReset,
+    SoftReset,
+    UnlockCommand,
+}
+
+// BootNotification charge point identity, None fields are not sent
+// box_serial and meter fields only exist in 1.6
+#[derive(Debug, Clone)]
+pub struct OcppIdentity {
+    pub vendor: String,
+    pub model: String,
+    pub firmware: Option<String>,
+    pub serial_number: Option<String>,
+    pub box_serial: Option<String>,
+    pub iccid: Option<String>,
+    pub imsi: Option<String>,
+    pub meter_type: Option<String>,
+    pub meter_serial: Option<String>,
+}
+
+impl OcppIdentity {
+    pub fn new(vendor: &str, model: &str) -> Self {
+        OcppIdentity {
+            vendor: vendor.to_string(),
+            model: model.to_string(),
+            firmware: None,
+            serial_number: None,
+            box_serial: None,
+            iccid: None,
+            imsi: None,
+            meter_type: None,
+            meter_serial: None,
+        }
+    }
+
+    // config value: 'text' literal, '@/path' file first line, '@/path#KEY' KEY=value file (e.g. /etc/os-release)
+    pub fn read_value(value: &str) -> Result<String, AfbError> {
+        let source = match value.strip_prefix('@') {
+            Some(source) => source,
+            None => return Ok(value.to_string()),
+        };
+        let (path, key) = match source.split_once('#') {
+            Some((path, key)) => (path, Some(key)),
+            None => (source, None),
+        };
+        let content = match std::fs::read_to_string(path) {
+            Ok(value) => value,
+            Err(error) => return afb_error!("ocpp-identity-file", "path:{} {}", path, error),
+        };
+
+        let line = match key {
+            None => content.lines().next(),
+            Some(key) => content.lines().find_map(|line| match line.split_once('=') {
+                Some((name, value)) if name.trim() == key => Some(value),
+                _ => None,
+            }),
+        };
+        match line {
+            Some(value) => Ok(value.trim().trim_matches('"').to_string()),
+            None => afb_error!("ocpp-identity-file", "path:{} no value for key:{:?}", path, key),
+        }
+    }
+
+    // reject values 1.6 CiString would truncate (2.0.1 limits are equal or larger)
+    pub fn check(&self) -> Result<(), AfbError> {
+        let fields = [
+            ("vendor", Some(&self.vendor), 20),
+            ("model", Some(&self.model), 20),
+            ("firmware", self.firmware.as_ref(), 50),
+            ("serial_number", self.serial_number.as_ref(), 25),
+            ("box_serial", self.box_serial.as_ref(), 25),
+            ("iccid", self.iccid.as_ref(), 20),
+            ("imsi", self.imsi.as_ref(), 20),
+            ("meter_type", self.meter_type.as_ref(), 25),
+            ("meter_serial", self.meter_serial.as_ref(), 25),
+        ];
+        for (name, value, max) in fields {
+            if let Some(value) = value {
+                if value.is_empty() || value.chars().count() > max {
+                    return afb_error!(
+                        "ocpp-identity-check",
+                        "{}:'{}' should be 1-{} characters",
+                        name,
+                        value,
+                        max
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub trait OcppChargePoint: Send + Sync {
+    fn get_version(&self) -> OcppVersion;
+
+    fn boot_notification(&self, identity: &OcppIdentity) -> Result<OcppQuery, AfbError>;
+    // interval is the heartbeat period when accepted, the retry delay otherwise
+    fn boot_notification_rsp(&self, payload: Value) -> Result<OcppBootResponse, AfbError>;
+
+    fn heartbeat(&self) -> Result<OcppQuery, AfbError>;
+    fn heartbeat_rsp(&self, payload: Value) -> Result<DateTime<Utc>, AfbError>;
+
+    fn authorize(&self, id_tag: &str) -> Result<OcppQuery, AfbError>;
+    // return true when id_tag is accepted
+    fn authorize_rsp(&self, payload: Value) -> Result<bool, AfbError>;
+    // idTagInfo carried by Authorize/StartTransaction/StopTransaction responses, kept in 1.6 format by OcppAuth
+    fn id_tag_info(&self, payload: &Value) -> Option<v106::IdTagInfo>;
+
+    fn start_transaction(&self, cid: u32, id_tag: &str) -> Result<OcppQuery, AfbError>;
+    // return the transaction id to login into manager connector
+    fn start_transaction_rsp(&self, cid: u32, payload: Value) -> Result<i32, AfbError>;
+    // start_transaction query could not reach csms, return the id used until it is replayed
+    fn start_transaction_offline(&self, mgr: &ManagerHandle, cid: u32) -> Result<i32, AfbError>;
+    // queued transaction query re-sent with csms transaction id
+    fn transaction_replay(&self, query: &OcppQuery, tid: i32) -> OcppQuery;
+    // transaction id assigned by csms to a replayed start, None when charge point owns ids
+    fn transaction_id(&self, payload: &Value) -> Result<Option<i32>, AfbError>;
+
+    // id_tag is the tag presented to stop, None for charge point initiated stops
+    fn stop_transaction(
+        &self,
+        mgr: &ManagerHandle,
+        cid: u32,
+        meter: i32,
+        reason: OcppStopReason,
+        id_tag: Option<&str>,
+    ) -> Result<OcppQuery, AfbError>;
+    fn stop_transaction_rsp(&self, payload: Value) -> Result<(), AfbError>;
+
+    fn status_notification(&self, cid: u32, status: &OcppChargerStatus) -> Result<OcppQuery, AfbError>;
+    fn status_notification_rsp(&self, payload: Value) -> Result<(), AfbError>;
+
+    fn meter_values(
+        &self,
+        mgr: &ManagerHandle,
+        cid: u32,
+        state: &EnergyState,
+    ) -> Result<OcppQuery, AfbError>;
+    fn meter_values_rsp(&self, payload: Value) -> Result<(), AfbError>;
+
+    // periodic messages keeping csms connection alive, empty when nothing should be sent
+    fn keep_alive(&self, mgr: &ManagerHandle) -> Result<Vec<OcppQuery>, AfbError>;
+}
+
+// return a static charge point for the selected protocol version
+pub fn new_charge_point(version: OcppVersion) -> &'static dyn OcppChargePoint {
+    match version {
+        OcppVersion::V106 => Box::leak(Box::new(ChargePointV106 {})),
+        OcppVersion::V201 => Box::leak(Box::new(ChargePointV201::new())),
+    }
+}
+
+pub struct ChargePointV106 {}
+
+impl ChargePointV106 {
+    fn sampled_value(
+        value: i32,
+        unit: v106::UnitOfMeasure,
+        measurand: v106::Measurand,
+    ) -> v106::SampledValue {
+        v106::SampledValue {
+            value: value.to_string(),
+            location: None, // string
+            context: None,  // string
+            phase: None,    // default L1
+            format: None,   // default Raw
+            unit: Some(unit),
+            measurand: Some(measurand),
+        }
+    }
+}
+
+impl OcppChargePoint for ChargePointV106 {
+    fn get_version(&self) -> OcppVersion {
+        OcppVersion::V106
+    }
+
+    fn boot_notification(&self, identity: &OcppIdentity) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v106::BootNotificationRequest {
+            charge_point_vendor: identity.vendor.clone(),
+            charge_point_model: identity.model.clone(),
+            firmware_version: identity.firmware.clone(),
+            charge_box_serial_number: identity.box_serial.clone(),
+            charge_point_serial_number: identity.serial_number.clone(),
+            iccid: identity.iccid.clone(),
+            imsi: identity.imsi.clone(),
+            meter_serial_number: identity.meter_serial.clone(),
+            meter_type: identity.meter_type.clone(),
+        })
+    }
+
+    fn boot_notification_rsp(&self, payload: Value) -> Result<OcppBootResponse, AfbError> {
+        let response = ocpp_decode::<v106::BootNotificationResponse>(payload)?;
+        let status = match response.status {
+            v106::RegistrationStatus::Accepted => OcppBootStatus::Accepted,
+            v106::RegistrationStatus::Pending => OcppBootStatus::Pending,
+            v106::RegistrationStatus::Rejected => OcppBootStatus::Rejected,
+        };
+        Ok(OcppBootResponse {
+            status,
+            interval: response.interval.max(0) as u32,
+            current_time: response.current_time,
+        })
+    }
+
+    fn heartbeat(&self) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v106::HeartbeatRequest {})
+    }
+
+    fn heartbeat_rsp(&self, payload: Value) -> Result<DateTime<Utc>, AfbError> {
+        let response = ocpp_decode::<v106::HeartbeatResponse>(payload)?;
+        Ok(response.current_time)
+    }
+
+    fn authorize(&self, id_tag: &str) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v106::AuthorizeRequest {
+            id_tag: id_tag.to_string(),
+        })
+    }
+
+    fn authorize_rsp(&self, payload: Value) -> Result<bool, AfbError> {
+        let response = ocpp_decode::<v106::AuthorizeResponse>(payload)?;
+        match response.id_tag_info.status {
+            v106::AuthorizationStatus::Accepted => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
+    fn id_tag_info(&self, payload: &Value) -> Option<v106::IdTagInfo> {
+        let info = payload.get("idTagInfo")?;
+        serde_json::from_value::<v106::IdTagInfo>(info.clone()).ok()
+    }
+
+    fn start_transaction(&self, cid: u32, id_tag: &str) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v106::StartTransactionRequest {
+            connector_id: cid,
+            id_tag: id_tag.to_string(),
+            meter_start: 0,
+            reservation_id: None,
+            timestamp: get_utc(),
+        })
+    }
+
+    fn start_transaction_rsp(&self, _cid: u32, payload: Value) -> Result<i32, AfbError> {
+        let response = ocpp_decode::<v106::StartTransactionResponse>(payload)?;
+        match response.id_tag_info.status {
+            v106::AuthorizationStatus::Accepted => Ok(response.transaction_id),
+            _ => afb_error!(
+                "ocpp-transaction-start",
+                "fail start:{:?}",
+                response.id_tag_info.status
+            ),
+        }
+    }
+
+    fn start_transaction_offline(&self, mgr: &ManagerHandle, _cid: u32) -> Result<i32, AfbError> {
+        mgr.provisional_tid()
+    }
+
+    // StopTransaction built offline carries the provisional id
+    fn transaction_replay(&self, query: &OcppQuery, tid: i32) -> OcppQuery {
+        let mut query = query.clone();
+        if query.action == "StopTransaction" {
+            query.payload["transactionId"] = json!(tid);
+        }
+        query
+    }
+
+    fn transaction_id(&self, payload: &Value) -> Result<Option<i32>, AfbError> {
+        let response = ocpp_decode::<v106::StartTransactionResponse>(payload.clone())?;
+        Ok(Some(response.transaction_id))
+    }
+
+    fn stop_transaction(
+        &self,
+        mgr: &ManagerHandle,
+        cid: u32,
+        meter: i32,
+        reason: OcppStopReason,
+        id_tag: Option<&str>,
+    ) -> Result<OcppQuery, AfbError> {
+        let reason = match reason {
+            OcppStopReason::Local => None,
+            OcppStopReason::Remote => Some(v106::Reason::Remote),
+            OcppStopReason::HardReset => Some(v106::Reason::HardReset),
+            OcppStopReason::SoftReset => Some(v106::Reason::SoftReset),
+            OcppStopReason::UnlockCommand => Some(v106::Reason::UnlockCommand),
+        };
+        OcppQuery::new(v106::StopTransactionRequest {
+            id_tag: id_tag.map(|value| value.to_string()),
+            meter_stop: meter,
+            timestamp: get_utc(),
+            reason,
+            transaction_data: None,
+            transaction_id: mgr.get_tid(cid)?,
+        })
+    }
+
+    fn stop_transaction_rsp(&self, payload: Value) -> Result<(), AfbError> {
+        ocpp_decode::<v106::StopTransactionResponse>(payload)?;
+        Ok(())
+    }
+
+    fn status_notification(&self, cid: u32, status: &OcppChargerStatus) -> Result<OcppQuery, AfbError> {
+        let mut error_code = v106::ChargePointErrorCode::NoError;
+        let charger_status = match status {
+            OcppChargerStatus::Charging => v106::ChargePointStatus::Charging,
+            OcppChargerStatus::Reserved => v106::ChargePointStatus::Reserved,
+            OcppChargerStatus::Unavailable => v106::ChargePointStatus::Unavailable,
+            OcppChargerStatus::Available => v106::ChargePointStatus::Available,
+            OcppChargerStatus::Finishing => v106::ChargePointStatus::Finishing,
+            OcppChargerStatus::Preparing => v106::ChargePointStatus::Preparing,
+            OcppChargerStatus::Error(err_code) => {
+                error_code = match err_code {
+                    OcppErrorCode::ConnectorLockFailure => {
+                        v106::ChargePointErrorCode::ConnectorLockFailure
+                    }
+                    OcppErrorCode::GroundFailure => v106::ChargePointErrorCode::GroundFailure,
+                    OcppErrorCode::HighTemperature => v106::ChargePointErrorCode::HighTemperature,
+                    OcppErrorCode::InternalError => v106::ChargePointErrorCode::InternalError,
+                    OcppErrorCode::NoError => v106::ChargePointErrorCode::NoError,
+                    OcppErrorCode::OtherError => v106::ChargePointErrorCode::OtherError,
+                    OcppErrorCode::OverCurrentFailure => {
+                        v106::ChargePointErrorCode::OverCurrentFailure
+                    }
+                    OcppErrorCode::OverVoltage => v106::ChargePointErrorCode::OverVoltage,
+                    OcppErrorCode::PowerMeterFailure => {
+                        v106::ChargePointErrorCode::PowerMeterFailure
+                    }
+                    OcppErrorCode::PowerSwitchFailure => {
+                        v106::ChargePointErrorCode::PowerSwitchFailure
+                    }
+                    OcppErrorCode::ReaderFailure => v106::ChargePointErrorCode::ReaderFailure,
+                    OcppErrorCode::UnderVoltage => v106::ChargePointErrorCode::UnderVoltage,
+                    OcppErrorCode::WeakSignal => v106::ChargePointErrorCode::WeakSignal,
+                };
+                v106::ChargePointStatus::Faulted
+            }
+        };
+
+        OcppQuery::new(v106::StatusNotificationRequest {
+            connector_id: cid,
+            error_code,
+            status: charger_status,
+            info: None,
+            timestamp: Some(get_utc()),
+            vendor_id: None,
+            vendor_error_code: None,
+        })
+    }
+
+    fn status_notification_rsp(&self, payload: Value) -> Result<(), AfbError> {
+        ocpp_decode::<v106::StatusNotificationResponse>(payload)?;
+        Ok(())
+    }
+
+    // ref: https://www.ampcontrol.io/ocpp-guide/how-to-send-ocpp-meter-values-with-metervalues-req
+    fn meter_values(
+        &self,
+        mgr: &ManagerHandle,
+        cid: u32,
+        state: &EnergyState,
+    ) -> Result<OcppQuery, AfbError> {
+        // triggered MeterValues may be sent without running transaction, provisional (negative) offline id is
+        // unknown to csms until StartTransaction is replayed
+        let tid = mgr.get_tid(cid)?;
+        OcppQuery::new(v106::MeterValuesRequest {
+            connector_id: cid,
+            transaction_id: if tid > 0 { Some(tid) } else { None },
+            meter_value: vec![v106::MeterValue {
+                timestamp: get_utc(),
+                sampled_value: vec![
+                    Self::sampled_value(
+                        state.tension / 1000,
+                        v106::UnitOfMeasure::V,
+                        v106::Measurand::Voltage,
+                    ),
+                    Self::sampled_value(
+                        state.power / 1000,
+                        v106::UnitOfMeasure::W,
+                        v106::Measurand::PowerActiveImport,
+                    ),
+                    Self::sampled_value(
+                        state.current / 1000,
+                        v106::UnitOfMeasure::A,
+                        v106::Measurand::CurrentImport,
+                    ),
+                    Self::sampled_value(
+                        state.session,
+                        v106::UnitOfMeasure::Wh,
+                        v106::Measurand::EnergyActiveImportRegister,
+                    ),
+                ],
+            }],
+        })
+    }
+
+    fn meter_values_rsp(&self, payload: Value) -> Result<(), AfbError> {
+        ocpp_decode::<v106::MeterValuesResponse>(payload)?;
+        Ok(())
+    }
+
+    // heartbeat answer syncs clock and restarts offline replay, 'available' status keeps OCPP from forgetting us
+    fn keep_alive(&self, mgr: &ManagerHandle) -> Result<Vec<OcppQuery>, AfbError> {
+        let mut queries = vec![self.heartbeat()?];
+        for cid in mgr.get_connectors() {
+            let status = mgr.get_status(cid)?;
+            if let OcppChargerStatus::Available = status {
+                queries.push(self.status_notification(cid, &status)?);
+            }
+        }
+        Ok(queries)
+    }
+}
+
+// 2.0.1 evse may hold multiple connectors, we only expose one per evse
+const V201_CONNECTOR_ID: i32 = 1;
+
+// 2.0.1 transaction id is generated by the charging station, seqno count messages within a transaction
+// pending and seqno are kept per evse (connector id)
+pub struct ChargePointV201State {
+    count: i32,
+    seqno: HashMap<u32, i32>,
+    pending: HashMap<u32, i32>,
+}
+
+pub struct ChargePointV201 {
+    data_set: Mutex<ChargePointV201State>,
+}
+
+impl Default for ChargePointV201 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChargePointV201 {
+    pub fn new() -> Self {
+        // transaction id seed, should not collide with previous binder run
+        let count = match unix_time() {
+            Ok(value) => (value.as_secs() % 1000000) as i32 * 100,
+            Err(_) => 0,
+        };
+        ChargePointV201 {
+            data_set: Mutex::new(ChargePointV201State {
+                count,
+                seqno: HashMap::new(),
+                pending: HashMap::new(),
+            }),
+        }
+    }
+
+    #[track_caller]
+    fn get_state(&self) -> Result<MutexGuard<'_, ChargePointV201State>, AfbError> {
+        match self.data_set.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-charge-point", "fail to lock v201 state"),
+        }
+    }
+
+    fn id_token(id_tag: &str) -> v201::IdTokenType {
+        v201::IdTokenType {
+            id_token: id_tag.to_string(),
+            kind: v201::IdTokenEnumType::ISO14443,
+            additional_info: None,
+        }
+    }
+
+    fn evse(cid: u32) -> Option<v201::EVSEType> {
+        Some(v201::EVSEType {
+            id: cid as i32,
+            connector_id: Some(V201_CONNECTOR_ID),
+        })
+    }
+
+    fn connector_status(status: &OcppChargerStatus) -> v201::ConnectorStatusEnumType {
+        match status {
+            OcppChargerStatus::Available => v201::ConnectorStatusEnumType::Available,
+            OcppChargerStatus::Reserved => v201::ConnectorStatusEnumType::Reserved,
+            OcppChargerStatus::Unavailable => v201::ConnectorStatusEnumType::Unavailable,
+            OcppChargerStatus::Preparing => v201::ConnectorStatusEnumType::Occupied,
+            OcppChargerStatus::Charging => v201::ConnectorStatusEnumType::Occupied,
+            OcppChargerStatus::Finishing => v201::ConnectorStatusEnumType::Occupied,
+            OcppChargerStatus::Error(_) => v201::ConnectorStatusEnumType::Faulted,
+        }
+    }
+
+    // sampled value type changed between rust-ocpp releases (f64|Decimal), build it from json
+    fn sampled_value(
+        value: i32,
+        measurand: &str,
+        unit: &str,
+    ) -> Result<v201::SampledValueType, AfbError> {
+        let jsonc = serde_json::json!({
+            "value": value,
+            "measurand": measurand,
+            "unitOfMeasure": {"unit": unit},
+        });
+        match serde_json::from_value::<v201::SampledValueType>(jsonc) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!(
+                "ocpp-sampled-value",
+                "measurand:{} error:{}",
+                measurand,
+                error
+            ),
+        }
+    }
+}
+
+impl OcppChargePoint for ChargePointV201 {
+    fn get_version(&self) -> OcppVersion {
+        OcppVersion::V201
+    }
+
+    fn boot_notification(&self, identity: &OcppIdentity) -> Result<OcppQuery, AfbError> {
+        let modem = match (&identity.iccid, &identity.imsi) {
+            (None, None) => None,
+            (iccid, imsi) => Some(v201::ModemType {
+                iccid: iccid.clone(),
+                imsi: imsi.clone(),
+            }),
+        };
+        OcppQuery::new(v201::BootNotificationRequest {
+            charging_station: v201::ChargingStationType {
+                serial_number: identity.serial_number.clone(),
+                model: identity.model.clone(),
+                vendor_name: identity.vendor.clone(),
+                firmware_version: identity.firmware.clone(),
+                modem,
+            },
+            reason: v201::BootReasonEnumType::PowerUp,
+        })
+    }
+
+    fn boot_notification_rsp(&self, payload: Value) -> Result<OcppBootResponse, AfbError> {
+        let response = ocpp_decode::<v201::BootNotificationResponse>(payload)?;
+        let status = match response.status {
+            v201::RegistrationStatusEnumType::Accepted => OcppBootStatus::Accepted,
+            v201::RegistrationStatusEnumType::Pending => OcppBootStatus::Pending,
+            v201::RegistrationStatusEnumType::Rejected => OcppBootStatus::Rejected,
+        };
+        Ok(OcppBootResponse {
+            status,
+            interval: response.interval.max(0) as u32,
+            current_time: response.current_time,
+        })
+    }
+
+    fn heartbeat(&self) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v201::HeartbeatRequest {})
+    }
+
+    fn heartbeat_rsp(&self, payload: Value) -> Result<DateTime<Utc>, AfbError> {
+        let response = ocpp_decode::<v201::HeartbeatResponse>(payload)?;
+        Ok(response.current_time)
+    }
+
+    fn authorize(&self, id_tag: &str) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v201::AuthorizeRequest {
+            id_token: Self::id_token(id_tag),
+            certificate: None,
+            iso15118_certificate_hash_data: None,
+        })
+    }
+
+    fn authorize_rsp(&self, payload: Value) -> Result<bool, AfbError> {
+        let response = ocpp_decode::<v201::AuthorizeResponse>(payload)?;
+        match response.id_token_info.status {
+            v201::AuthorizationStatusEnumType::Accepted => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
+    // groupIdToken is 1.6 parentIdTag, 2.0.1 specific statuses are Invalid
+    fn id_tag_info(&self, payload: &Value) -> Option<v106::IdTagInfo> {
+        let info = payload.get("idTokenInfo")?;
+        let info = serde_json::from_value::<v201::IdTokenInfoType>(info.clone()).ok()?;
+        let status = match info.status {
+            v201::AuthorizationStatusEnumType::Accepted => v106::AuthorizationStatus::Accepted,
+            v201::AuthorizationStatusEnumType::Blocked => v106::AuthorizationStatus::Blocked,
+            v201::AuthorizationStatusEnumType::ConcurrentTx => v106::AuthorizationStatus::ConcurrentTx,
+            v201::AuthorizationStatusEnumType::Expired => v106::AuthorizationStatus::Expired,
+            _ => v106::AuthorizationStatus::Invalid,
+        };
+        Some(v106::IdTagInfo {
+            expiry_date: info.cache_expiry_date_time,
+            parent_id_tag: info.group_id_token.map(|token| token.id_token),
+            status,
+        })
+    }
+
+    fn start_transaction(&self, cid: u32, id_tag: &str) -> Result<OcppQuery, AfbError> {
+        let mut data_set = self.get_state()?;
+        data_set.count += 1;
+        let tid = data_set.count;
+        data_set.seqno.insert(cid, 0);
+        data_set.pending.insert(cid, tid);
+
+        OcppQuery::new(v201::TransactionEventRequest {
+            event_type: v201::TransactionEventEnumType::Started,
+            timestamp: get_utc(),
+            trigger_reason: v201::TriggerReasonEnumType::Authorized,
+            seq_no: 0,
+            offline: None,
+            number_of_phases_used: None,
+            cable_max_current: None,
+            reservation_id: None,
+            transaction_info: v201::TransactionType {
+                transaction_id: tid.to_string(),
+                charging_state: Some(v201::ChargingStateEnumType::EVConnected),
+                time_spent_charging: None,
+                stopped_reason: None,
+                remote_start_id: None,
+            },
+            id_token: Some(Self::id_token(id_tag)),
+            evse: Self::evse(cid),
+            meter_value: None,
+        })
+    }
+
+    fn start_transaction_rsp(&self, cid: u32, payload: Value) -> Result<i32, AfbError> {
+        let response = ocpp_decode::<v201::TransactionEventResponse>(payload)?;
+
+        // idTokenInfo is only present when transaction started with a token
+        if let Some(info) = &response.id_token_info {
+            match info.status {
+                v201::AuthorizationStatusEnumType::Accepted => {}
+                _ => return afb_error!("ocpp-transaction-start", "fail start:{:?}", info.status),
+            }
+        }
+
+        let mut data_set = self.get_state()?;
+        match data_set.pending.remove(&cid) {
+            Some(tid) => Ok(tid),
+            None => afb_error!("ocpp-transaction-start", "no pending transaction connector:{}", cid),
+        }
+    }
+
+    // transaction id is already owned by the charging station
+    fn start_transaction_offline(&self, _mgr: &ManagerHandle, cid: u32) -> Result<i32, AfbError> {
+        let mut data_set = self.get_state()?;
+        match data_set.pending.remove(&cid) {
+            Some(tid) => Ok(tid),
+            None => afb_error!("ocpp-transaction-start", "no pending transaction connector:{}", cid),
+        }
+    }
+
+    // events that occurred while offline are flagged (OCPP-2.0.1 E04)
+    fn transaction_replay(&self, query: &OcppQuery, _tid: i32) -> OcppQuery {
+        let mut query = query.clone();
+        query.payload["offline"] = json!(true);
+        query
+    }
+
+    fn transaction_id(&self, payload: &Value) -> Result<Option<i32>, AfbError> {
+        ocpp_decode::<v201::TransactionEventResponse>(payload.clone())?;
+        Ok(None)
+    }
+
+    fn stop_transaction(
+        &self,
+        mgr: &ManagerHandle,
+        cid: u32,
+        meter: i32,
+        reason: OcppStopReason,
+        id_tag: Option<&str>,
+    ) -> Result<OcppQuery, AfbError> {
+        // 2.0.1 has no hard/soft reset distinction
+        let (trigger_reason, stopped_reason) = match reason {
+            OcppStopReason::Local => (v201::TriggerReasonEnumType::StopAuthorized, v201::ReasonEnumType::Local),
+            OcppStopReason::Remote => (v201::TriggerReasonEnumType::RemoteStop, v201::ReasonEnumType::Remote),
+            OcppStopReason::HardReset | OcppStopReason::SoftReset => {
+                (v201::TriggerReasonEnumType::ResetCommand, v201::ReasonEnumType::ImmediateReset)
+            }
+            OcppStopReason::UnlockCommand => (v201::TriggerReasonEnumType::UnlockCommand, v201::ReasonEnumType::Other),
+        };
+        let tid = mgr.get_tid(cid)?;
+        let mut data_set = self.get_state()?;
+        let seqno = data_set.seqno.entry(cid).or_insert(0);
+        *seqno += 1;
+        let seqno = *seqno;
+
+        OcppQuery::new(v201::TransactionEventRequest {
+            event_type: v201::TransactionEventEnumType::Ended,
+            timestamp: get_utc(),
+            trigger_reason,
+            seq_no: seqno,
+            offline: None,
+            number_of_phases_used: None,
+            cable_max_current: None,
+            reservation_id: None,
+            transaction_info: v201::TransactionType {
+                transaction_id: tid.to_string(),
+                charging_state: Some(v201::ChargingStateEnumType::Idle),
+                time_spent_charging: None,
+                stopped_reason: Some(stopped_reason),
+                remote_start_id: None,
+            },
+            id_token: id_tag.map(Self::id_token),
+            evse: Self::evse(cid),
+            meter_value: Some(vec![v201::MeterValueType {
+                timestamp: get_utc(),
+                sampled_value: vec![Self::sampled_value(
+                    meter,
+                    "Energy.Active.Import.Register",
+                    "Wh",
+                )?],
+            }]),
+        })
+    }
+
+    fn stop_transaction_rsp(&self, payload: Value) -> Result<(), AfbError> {
+        ocpp_decode::<v201::TransactionEventResponse>(payload)?;
+        Ok(())
+    }
+
+    fn status_notification(&self, cid: u32, status: &OcppChargerStatus) -> Result<OcppQuery, AfbError> {
+        OcppQuery::new(v201::StatusNotificationRequest {
+            timestamp: get_utc(),
+            connector_status: Self::connector_status(status),
+            evse_id: cid as i32,
+            connector_id: V201_CONNECTOR_ID,
+        })
+    }
+
+    fn status_notification_rsp(&self, payload: Value) -> Result<(), AfbError> {
+        ocpp_decode::<v201::StatusNotificationResponse>(payload)?;
+        Ok(())
+    }
+
+    fn meter_values(
+        &self,
+        mgr: &ManagerHandle,
+        cid: u32,
+        state: &EnergyState,
+    ) -> Result<OcppQuery, AfbError> {
+        // evse is checked, transaction is not referenced by 2.0.1 MeterValues
+        mgr.get_tid(cid)?;
+        OcppQuery::new(v201::MeterValuesRequest {
+            evse_id: cid as i32,
+            meter_value: vec![v201::MeterValueType {
+                timestamp: get_utc(),
+                sampled_value: vec![
+                    Self::sampled_value(state.tension / 1000, "Voltage", "V")?,
+                    Self::sampled_value(state.power / 1000, "Power.Active.Import", "W")?,
+                    Self::sampled_value(state.current / 1000, "Current.Import", "A")?,
+                    Self::sampled_value(state.session, "Energy.Active.Import.Register", "Wh")?,
+                ],
+            }],
+        })
+    }
+
+    fn meter_values_rsp(&self, payload: Value) -> Result<(), AfbError> {
+        ocpp_decode::<v201::MeterValuesResponse>(payload)?;
+        Ok(())
+    }
+
+    // 2.0.1 uses heartbeat to keep connection live, status is only sent on change
+    fn keep_alive(&self, _mgr: &ManagerHandle) -> Result<Vec<OcppQuery>, AfbError> {
+        Ok(vec![self.heartbeat()?])
+    }
+}
@@ -0,0 +1,761 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Reference: OCPP-1.6 §9 standard configuration key names & values
+ */
+
+// Charge point configuration keys, values are kept as OCPP strings and checked against
+// their kind on change. Consumers (timers, verbs) read them live from the store.
+
+use crate::prelude::*;
+#[cfg(feature = "afbv4")]
+use afbv4::prelude::*;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::sync::{Mutex, MutexGuard};
+use strum_macros::Display;
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+
+// 1.6 core profile key names
+pub const CFG_ALLOW_OFFLINE_TX_FOR_UNKNOWN_ID: &str = "AllowOfflineTxForUnknownId";
+pub const CFG_AUTHORIZATION_CACHE_ENABLED: &str = "AuthorizationCacheEnabled";
+pub const CFG_AUTHORIZE_REMOTE_TX_REQUESTS: &str = "AuthorizeRemoteTxRequests";
+pub const CFG_CLOCK_ALIGNED_DATA_INTERVAL: &str = "ClockAlignedDataInterval";
+pub const CFG_CONNECTION_TIMEOUT: &str = "ConnectionTimeOut";
+pub const CFG_GET_CONFIGURATION_MAX_KEYS: &str = "GetConfigurationMaxKeys";
+pub const CFG_HEARTBEAT_INTERVAL: &str = "HeartbeatInterval";
+pub const CFG_LOCAL_AUTHORIZE_OFFLINE: &str = "LocalAuthorizeOffline";
+pub const CFG_LOCAL_AUTH_LIST_ENABLED: &str = "LocalAuthListEnabled";
+pub const CFG_LOCAL_AUTH_LIST_MAX_LENGTH: &str = "LocalAuthListMaxLength";
+pub const CFG_LOCAL_PRE_AUTHORIZE: &str = "LocalPreAuthorize";
+pub const CFG_METER_VALUES_ALIGNED_DATA: &str = "MeterValuesAlignedData";
+pub const CFG_METER_VALUES_SAMPLED_DATA: &str = "MeterValuesSampledData";
+pub const CFG_METER_VALUE_SAMPLE_INTERVAL: &str = "MeterValueSampleInterval";
+pub const CFG_NUMBER_OF_CONNECTORS: &str = "NumberOfConnectors";
+pub const CFG_RESET_RETRIES: &str = "ResetRetries";
+pub const CFG_RESERVE_CONNECTOR_ZERO_SUPPORTED: &str = "ReserveConnectorZeroSupported";
+pub const CFG_SEND_LOCAL_LIST_MAX_LENGTH: &str = "SendLocalListMaxLength";
+pub const CFG_STOP_TRANSACTION_ON_EV_SIDE_DISCONNECT: &str = "StopTransactionOnEVSideDisconnect";
+pub const CFG_STOP_TRANSACTION_ON_INVALID_ID: &str = "StopTransactionOnInvalidId";
+pub const CFG_STOP_TXN_ALIGNED_DATA: &str = "StopTxnAlignedData";
+pub const CFG_STOP_TXN_SAMPLED_DATA: &str = "StopTxnSampledData";
+pub const CFG_SUPPORTED_FEATURE_PROFILES: &str = "SupportedFeatureProfiles";
+pub const CFG_TRANSACTION_MESSAGE_ATTEMPTS: &str = "TransactionMessageAttempts";
+pub const CFG_TRANSACTION_MESSAGE_RETRY_INTERVAL: &str = "TransactionMessageRetryInterval";
+pub const CFG_UNLOCK_CONNECTOR_ON_EV_SIDE_DISCONNECT: &str = "UnlockConnectorOnEVSideDisconnect";
+pub const CFG_WEBSOCKET_PING_INTERVAL: &str = "WebSocketPingInterval";
+
+// bump when persisted file layout changes, older files are ignored
+pub const OCPP_CONFIG_SCHEMA: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum OcppKeyKind {
+    Boolean,
+    Integer,
+    CsvList,
+    Text,
+}
+
+// protocol neutral ChangeConfiguration status
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum OcppKeyStatus {
+    Accepted,
+    Rejected,
+    RebootRequired,
+    NotSupported,
+}
+
+#[derive(Debug, Clone)]
+pub struct OcppConfigKey {
+    pub name: &'static str,
+    pub kind: OcppKeyKind,
+    // csms cannot change readonly keys
+    pub readonly: bool,
+    // new value only applies after a reboot
+    pub reboot: bool,
+    pub value: String,
+}
+
+impl OcppConfigKey {
+    pub fn new(name: &'static str, kind: OcppKeyKind, readonly: bool, value: &str) -> Self {
+        OcppConfigKey {
+            name,
+            kind,
+            readonly,
+            reboot: false,
+            value: value.to_string(),
+        }
+    }
+
+    pub fn set_reboot(mut self) -> Self {
+        self.reboot = true;
+        self
+    }
+
+    // return normalized value or an error when value does not match key kind
+    pub fn check(&self, value: &str) -> Result<String, AfbError> {
+        let value = value.trim();
+        match self.kind {
+            OcppKeyKind::Boolean => match value.to_lowercase().as_str() {
+                "true" => Ok("true".to_string()),
+                "false" => Ok("false".to_string()),
+                _ => afb_error!(
+                    "ocpp-property-constraint",
+                    "key:{} invalid boolean:{}",
+                    self.name,
+                    value
+                ),
+            },
+            OcppKeyKind::Integer => match value.parse::<u32>() {
+                Ok(number) => Ok(number.to_string()),
+                Err(_) => afb_error!(
+                    "ocpp-property-constraint",
+                    "key:{} invalid integer:{}",
+                    self.name,
+                    value
+                ),
+            },
+            OcppKeyKind::CsvList => Ok(value
+                .split(',')
+                .map(|item| item.trim())
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<&str>>()
+                .join(",")),
+            OcppKeyKind::Text => Ok(value.to_string()),
+        }
+    }
+}
+
+// default 1.6 core profile keys, heartbeat is initialized from binding 'tic'
+pub fn v106_core_keys(heartbeat: u32, connectors: u32) -> Vec<OcppConfigKey> {
+    use OcppKeyKind::*;
+    vec![
+        OcppConfigKey::new(CFG_ALLOW_OFFLINE_TX_FOR_UNKNOWN_ID, Boolean, false, "false"),
+        OcppConfigKey::new(CFG_AUTHORIZATION_CACHE_ENABLED, Boolean, false, "true"),
+        OcppConfigKey::new(CFG_AUTHORIZE_REMOTE_TX_REQUESTS, Boolean, false, "true"),
+        OcppConfigKey::new(CFG_CLOCK_ALIGNED_DATA_INTERVAL, Integer, false, "0"),
+        OcppConfigKey::new(CFG_CONNECTION_TIMEOUT, Integer, false, "60"),
+        OcppConfigKey::new(CFG_GET_CONFIGURATION_MAX_KEYS, Integer, true, "50"),
+        OcppConfigKey::new(
+            CFG_HEARTBEAT_INTERVAL,
+            Integer,
+            false,
+            &heartbeat.to_string(),
+        ),
+        OcppConfigKey::new(CFG_LOCAL_AUTHORIZE_OFFLINE, Boolean, false, "false"),
+        OcppConfigKey::new(CFG_LOCAL_PRE_AUTHORIZE, Boolean, false, "false"),
+        // local auth list management profile
+        OcppConfigKey::new(CFG_LOCAL_AUTH_LIST_ENABLED, Boolean, false, "true"),
+        OcppConfigKey::new(CFG_LOCAL_AUTH_LIST_MAX_LENGTH, Integer, true, "1000"),
+        OcppConfigKey::new(CFG_METER_VALUES_ALIGNED_DATA, CsvList, false, ""),
+        OcppConfigKey::new(
+            CFG_METER_VALUES_SAMPLED_DATA,
+            CsvList,
+            false,
+            "Energy.Active.Import.Register",
+        ),
+        OcppConfigKey::new(CFG_METER_VALUE_SAMPLE_INTERVAL, Integer, false, "60"),
+        OcppConfigKey::new(
+            CFG_NUMBER_OF_CONNECTORS,
+            Integer,
+            true,
+            &connectors.to_string(),
+        ),
+        // reservation profile, follows binding 'connector_zero' quirk flag
+        OcppConfigKey::new(CFG_RESERVE_CONNECTOR_ZERO_SUPPORTED, Boolean, true, "true"),
+        OcppConfigKey::new(CFG_RESET_RETRIES, Integer, false, "1"),
+        OcppConfigKey::new(CFG_SEND_LOCAL_LIST_MAX_LENGTH, Integer, true, "100"),
+        OcppConfigKey::new(
+            CFG_STOP_TRANSACTION_ON_EV_SIDE_DISCONNECT,
+            Boolean,
+            false,
+            "true",
+        ),
+        OcppConfigKey::new(CFG_STOP_TRANSACTION_ON_INVALID_ID, Boolean, false, "true"),
+        OcppConfigKey::new(CFG_STOP_TXN_ALIGNED_DATA, CsvList, false, ""),
+        OcppConfigKey::new(CFG_STOP_TXN_SAMPLED_DATA, CsvList, false, ""),
+        OcppConfigKey::new(
+            CFG_SUPPORTED_FEATURE_PROFILES,
+            CsvList,
+            true,
+            "Core,LocalAuthListManagement,RemoteTrigger,Reservation,SmartCharging",
+        ),
+        OcppConfigKey::new(CFG_TRANSACTION_MESSAGE_ATTEMPTS, Integer, false, "3"),
+        OcppConfigKey::new(CFG_TRANSACTION_MESSAGE_RETRY_INTERVAL, Integer, false, "60"),
+        OcppConfigKey::new(
+            CFG_UNLOCK_CONNECTOR_ON_EV_SIDE_DISCONNECT,
+            Boolean,
+            false,
+            "true",
+        ),
+        // websocket is owned by OCPP transport extension, ping is only read at connection time
+        OcppConfigKey::new(CFG_WEBSOCKET_PING_INTERVAL, Integer, false, "0").set_reboot(),
+    ]
+}
+
+pub struct OcppConfigStore {
+    data_set: Mutex<Vec<OcppConfigKey>>,
+    defaults: Vec<OcppConfigKey>,
+    // binding state persisted with keys but never exposed to csms (e.g. connector availability)
+    private: Mutex<serde_json::Map<String, Value>>,
+    // csms changes are persisted when defined
+    path: Option<String>,
+}
+
+impl OcppConfigStore {
+    pub fn new(keys: Vec<OcppConfigKey>, path: Option<&str>) -> &'static Self {
+        let handle = OcppConfigStore {
+            data_set: Mutex::new(keys.clone()),
+            defaults: keys,
+            private: Mutex::new(serde_json::Map::new()),
+            path: path.map(|value| value.to_string()),
+        };
+        Box::leak(Box::new(handle))
+    }
+
+    pub fn get_path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    // override defaults with persisted values, return the number of restored keys
+    pub fn load(&self) -> Result<usize, AfbError> {
+        let path = match &self.path {
+            Some(value) => value,
+            None => return Ok(0),
+        };
+
+        let text = match fs::read_to_string(path) {
+            Ok(value) => value,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(error) => return afb_error!("ocpp-config-load", "path:{} {}", path, error),
+        };
+
+        let jsonc = match serde_json::from_str::<Value>(&text) {
+            Ok(value) => value,
+            Err(error) => return afb_error!("ocpp-config-load", "path:{} {}", path, error),
+        };
+
+        let schema = jsonc.get("schema").and_then(|value| value.as_u64());
+        if schema != Some(OCPP_CONFIG_SCHEMA) {
+            return afb_error!(
+                "ocpp-config-load",
+                "path:{} schema:{:?} != {}",
+                path,
+                schema,
+                OCPP_CONFIG_SCHEMA
+            );
+        }
+
+        let values = match jsonc.get("keys").and_then(|value| value.as_object()) {
+            Some(value) => value,
+            None => return afb_error!("ocpp-config-load", "path:{} missing keys", path),
+        };
+
+        // private section is optional (files written before it existed)
+        if let Some(private) = jsonc.get("private").and_then(|value| value.as_object()) {
+            *self.private.lock().unwrap() = private.clone();
+        }
+
+        // unknown, readonly or invalid keys are silently dropped
+        let mut count = 0;
+        let mut data_set = self.get_state()?;
+        for key in data_set.iter_mut().filter(|key| !key.readonly) {
+            if let Some(Value::String(value)) = values.get(key.name) {
+                if let Ok(value) = key.check(value) {
+                    key.value = value;
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    // write to a temporary file then rename, a power cut never leaves a truncated file
+    fn save(&self, keys: &[OcppConfigKey]) -> Result<(), AfbError> {
+        let path = match &self.path {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+
+        let mut values = serde_json::Map::new();
+        for key in keys.iter().filter(|key| !key.readonly) {
+            values.insert(key.name.to_string(), Value::String(key.value.clone()));
+        }
+        let private = self.private.lock().unwrap().clone();
+        let jsonc = json!({"schema": OCPP_CONFIG_SCHEMA, "keys": values, "private": private});
+
+        let tmpname = format!("{}.tmp", path);
+        let status = std::path::Path::new(path)
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::File::create(&tmpname))
+            .and_then(|mut file| {
+                file.write_all(jsonc.to_string().as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmpname, path));
+
+        match status {
+            Ok(()) => Ok(()),
+            Err(error) => afb_error!("ocpp-config-save", "path:{} {}", path, error),
+        }
+    }
+
+    pub fn dump(&self) -> Result<Value, AfbError> {
+        let data_set = self.get_state()?;
+        let keys = data_set
+            .iter()
+            .map(|key| {
+                json!({
+                    "key": key.name,
+                    "value": key.value,
+                    "readonly": key.readonly,
+                    "reboot": key.reboot,
+                })
+            })
+            .collect::<Vec<Value>>();
+        let private = self.private.lock().unwrap().clone();
+        Ok(json!({
+            "schema": OCPP_CONFIG_SCHEMA,
+            "path": self.path,
+            "keys": keys,
+            "private": private,
+        }))
+    }
+
+    // restore binding defaults and drop persisted file
+    pub fn reset(&self) -> Result<(), AfbError> {
+        let mut data_set = self.get_state()?;
+        *data_set = self.defaults.clone();
+        self.private.lock().unwrap().clear();
+        if let Some(path) = &self.path {
+            match fs::remove_file(path) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => return afb_error!("ocpp-config-reset", "path:{} {}", path, error),
+            }
+        }
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn get_state(&self) -> Result<MutexGuard<'_, Vec<OcppConfigKey>>, AfbError> {
+        match self.data_set.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-config-store", "fail to lock configuration"),
+        }
+    }
+
+    pub fn get_private(&self, name: &str) -> Option<Value> {
+        self.private.lock().unwrap().get(name).cloned()
+    }
+
+    // update binding private state and persist it with current keys
+    pub fn set_private(&self, name: &str, value: Value) -> Result<(), AfbError> {
+        self.private.lock().unwrap().insert(name.to_string(), value);
+        let data_set = self.get_state()?;
+        self.save(&data_set)
+    }
+
+    // OCPP keys are case-insensitive
+    pub fn get_key(&self, name: &str) -> Result<OcppConfigKey, AfbError> {
+        let data_set = self.get_state()?;
+        match data_set
+            .iter()
+            .find(|key| key.name.eq_ignore_ascii_case(name))
+        {
+            Some(key) => Ok(key.clone()),
+            None => afb_error!("ocpp-not-supported", "unknown configuration key:{}", name),
+        }
+    }
+
+    pub fn get_value(&self, name: &str) -> Result<String, AfbError> {
+        Ok(self.get_key(name)?.value)
+    }
+
+    pub fn get_u32(&self, name: &str) -> Result<u32, AfbError> {
+        let value = self.get_value(name)?;
+        match value.parse::<u32>() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-config-store", "key:{} not an integer:{}", name, value),
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Result<bool, AfbError> {
+        Ok(self.get_value(name)? == "true")
+    }
+
+    // local update (binding config, boot response...), readonly flag is ignored
+    pub fn set_value(&self, name: &str, value: &str) -> Result<(), AfbError> {
+        let mut data_set = self.get_state()?;
+        match data_set
+            .iter_mut()
+            .find(|key| key.name.eq_ignore_ascii_case(name))
+        {
+            Some(key) => {
+                key.value = key.check(value)?;
+                Ok(())
+            }
+            None => afb_error!("ocpp-not-supported", "unknown configuration key:{}", name),
+        }
+    }
+
+    // csms update (ChangeConfiguration)
+    pub fn change(&self, name: &str, value: &str) -> Result<OcppKeyStatus, AfbError> {
+        let mut data_set = self.get_state()?;
+        let index = match data_set
+            .iter()
+            .position(|key| key.name.eq_ignore_ascii_case(name))
+        {
+            Some(index) => index,
+            None => return Ok(OcppKeyStatus::NotSupported),
+        };
+
+        let key = &mut data_set[index];
+        if key.readonly {
+            return Ok(OcppKeyStatus::Rejected);
+        }
+
+        let value = match key.check(value) {
+            Ok(value) => value,
+            Err(_) => return Ok(OcppKeyStatus::Rejected),
+        };
+
+        let status = if key.reboot {
+            OcppKeyStatus::RebootRequired
+        } else {
+            OcppKeyStatus::Accepted
+        };
+
+        // csms receives an error when persistence fails, previous value is restored
+        let previous = std::mem::replace(&mut key.value, value);
+        if let Err(error) = self.save(&data_set) {
+            data_set[index].value = previous;
+            return Err(error);
+        }
+        Ok(status)
+    }
+
+    // return requested keys (all when None) and the list of unknown ones
+    pub fn get_keys(
+        &self,
+        names: Option<&Vec<String>>,
+    ) -> Result<(Vec<OcppConfigKey>, Vec<String>), AfbError> {
+        let data_set = self.get_state()?;
+        let names = match names {
+            Some(value) if !value.is_empty() => value,
+            _ => return Ok((data_set.clone(), Vec::new())),
+        };
+
+        let mut keys = Vec::new();
+        let mut unknowns = Vec::new();
+        for name in names {
+            match data_set
+                .iter()
+                .find(|key| key.name.eq_ignore_ascii_case(name))
+            {
+                Some(key) => keys.push(key.clone()),
+                None => unknowns.push(name.clone()),
+            }
+        }
+        Ok((keys, unknowns))
+    }
+
+    pub fn get_configuration(
+        &self,
+        request: &v106::GetConfigurationRequest,
+    ) -> Result<v106::GetConfigurationResponse, AfbError> {
+        let max_keys = self.get_u32(CFG_GET_CONFIGURATION_MAX_KEYS)? as usize;
+        if let Some(names) = &request.key {
+            if names.len() > max_keys {
+                return afb_error!(
+                    "ocpp-occurrence-constraint",
+                    "requested keys:{} > {}:{}",
+                    names.len(),
+                    CFG_GET_CONFIGURATION_MAX_KEYS,
+                    max_keys
+                );
+            }
+        }
+
+        let (keys, unknowns) = self.get_keys(request.key.as_ref())?;
+        let configuration_key = keys
+            .iter()
+            .map(|key| v106::KeyValue {
+                key: key.name.to_string(),
+                readonly: key.readonly,
+                value: Some(key.value.clone()),
+            })
+            .collect::<Vec<v106::KeyValue>>();
+
+        Ok(v106::GetConfigurationResponse {
+            configuration_key: Some(configuration_key),
+            unknown_key: if unknowns.is_empty() {
+                None
+            } else {
+                Some(unknowns)
+            },
+        })
+    }
+
+    pub fn change_configuration(
+        &self,
+        request: &v106::ChangeConfigurationRequest,
+    ) -> Result<v106::ChangeConfigurationResponse, AfbError> {
+        let status = match self.change(&request.key, &request.value)? {
+            OcppKeyStatus::Accepted => v106::ConfigurationStatus::Accepted,
+            OcppKeyStatus::Rejected => v106::ConfigurationStatus::Rejected,
+            OcppKeyStatus::RebootRequired => v106::ConfigurationStatus::RebootRequired,
+            OcppKeyStatus::NotSupported => v106::ConfigurationStatus::NotSupported,
+        };
+        Ok(v106::ChangeConfigurationResponse { status })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // in-memory store, nothing is written to disk
+    fn store_new() -> &'static OcppConfigStore {
+        OcppConfigStore::new(v106_core_keys(60, 2), None)
+    }
+
+    // one file per test, tests run in parallel
+    fn store_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("ocpp-config-{}-{}.json", name, std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(format!("{}.tmp", path));
+        path
+    }
+
+    #[test]
+    fn key_check() {
+        let key = OcppConfigKey::new("Flag", OcppKeyKind::Boolean, false, "false");
+        assert_eq!(key.check(" TRUE ").unwrap(), "true");
+        assert_eq!(key.check("False").unwrap(), "false");
+        assert!(key.check("1").is_err());
+
+        let key = OcppConfigKey::new("Count", OcppKeyKind::Integer, false, "0");
+        assert_eq!(key.check("042").unwrap(), "42");
+        assert!(key.check("-1").is_err());
+        assert!(key.check("ten").is_err());
+
+        let key = OcppConfigKey::new("List", OcppKeyKind::CsvList, false, "");
+        assert_eq!(key.check(" Voltage, ,Current.Import ").unwrap(), "Voltage,Current.Import");
+
+        let key = OcppConfigKey::new("Name", OcppKeyKind::Text, false, "");
+        assert_eq!(key.check(" any value ").unwrap(), "any value");
+    }
+
+    #[test]
+    fn change_status() {
+        let store = store_new();
+
+        // keys are case-insensitive and normalized
+        let status = store.change("heartbeatinterval", "120").unwrap();
+        assert_eq!(status, OcppKeyStatus::Accepted);
+        assert_eq!(store.get_u32(CFG_HEARTBEAT_INTERVAL).unwrap(), 120);
+
+        let status = store.change(CFG_LOCAL_PRE_AUTHORIZE, "TRUE").unwrap();
+        assert_eq!(status, OcppKeyStatus::Accepted);
+        assert!(store.get_bool(CFG_LOCAL_PRE_AUTHORIZE).unwrap());
+
+        let status = store.change("NoSuchKey", "1").unwrap();
+        assert_eq!(status, OcppKeyStatus::NotSupported);
+
+        let status = store.change(CFG_WEBSOCKET_PING_INTERVAL, "30").unwrap();
+        assert_eq!(status, OcppKeyStatus::RebootRequired);
+        assert_eq!(store.get_value(CFG_WEBSOCKET_PING_INTERVAL).unwrap(), "30");
+    }
+
+    #[test]
+    fn change_rejected() {
+        let store = store_new();
+
+        // readonly keys only change locally
+        let status = store.change(CFG_NUMBER_OF_CONNECTORS, "4").unwrap();
+        assert_eq!(status, OcppKeyStatus::Rejected);
+        assert_eq!(store.get_u32(CFG_NUMBER_OF_CONNECTORS).unwrap(), 2);
+        store.set_value(CFG_NUMBER_OF_CONNECTORS, "4").unwrap();
+        assert_eq!(store.get_u32(CFG_NUMBER_OF_CONNECTORS).unwrap(), 4);
+
+        // value not matching key kind leaves previous value
+        let status = store.change(CFG_HEARTBEAT_INTERVAL, "soon").unwrap();
+        assert_eq!(status, OcppKeyStatus::Rejected);
+        assert_eq!(store.get_u32(CFG_HEARTBEAT_INTERVAL).unwrap(), 60);
+
+        let status = store.change(CFG_AUTHORIZATION_CACHE_ENABLED, "yes").unwrap();
+        assert_eq!(status, OcppKeyStatus::Rejected);
+        assert!(store.get_bool(CFG_AUTHORIZATION_CACHE_ENABLED).unwrap());
+
+        assert!(store.set_value(CFG_HEARTBEAT_INTERVAL, "-5").is_err());
+        assert!(store.set_value("NoSuchKey", "1").is_err());
+    }
+
+    #[test]
+    fn get_configuration() {
+        let store = store_new();
+        let request = serde_json::from_value::<v106::GetConfigurationRequest>(json!({
+            "key": [CFG_HEARTBEAT_INTERVAL, "NoSuchKey"],
+        }))
+        .unwrap();
+        let response = store.get_configuration(&request).unwrap();
+        let keys = response.configuration_key.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].key, CFG_HEARTBEAT_INTERVAL);
+        assert_eq!(keys[0].value.as_deref(), Some("60"));
+        assert_eq!(response.unknown_key, Some(vec!["NoSuchKey".to_string()]));
+
+        // no key returns every key
+        let request = serde_json::from_value::<v106::GetConfigurationRequest>(json!({})).unwrap();
+        let response = store.get_configuration(&request).unwrap();
+        assert_eq!(response.configuration_key.unwrap().len(), v106_core_keys(60, 2).len());
+        assert_eq!(response.unknown_key, None);
+
+        // more keys than GetConfigurationMaxKeys is a csms error
+        store.set_value(CFG_GET_CONFIGURATION_MAX_KEYS, "1").unwrap();
+        let request = serde_json::from_value::<v106::GetConfigurationRequest>(json!({
+            "key": [CFG_HEARTBEAT_INTERVAL, CFG_CONNECTION_TIMEOUT],
+        }))
+        .unwrap();
+        assert!(store.get_configuration(&request).is_err());
+    }
+
+    #[test]
+    fn change_configuration() {
+        let store = store_new();
+        let request = serde_json::from_value::<v106::ChangeConfigurationRequest>(json!({
+            "key": CFG_CONNECTION_TIMEOUT,
+            "value": "30",
+        }))
+        .unwrap();
+        let response = store.change_configuration(&request).unwrap();
+        assert!(matches!(response.status, v106::ConfigurationStatus::Accepted));
+
+        let request = serde_json::from_value::<v106::ChangeConfigurationRequest>(json!({
+            "key": CFG_GET_CONFIGURATION_MAX_KEYS,
+            "value": "10",
+        }))
+        .unwrap();
+        let response = store.change_configuration(&request).unwrap();
+        assert!(matches!(response.status, v106::ConfigurationStatus::Rejected));
+    }
+
+    #[test]
+    fn save_load() {
+        let path = store_path("save-load");
+        let store = OcppConfigStore::new(v106_core_keys(60, 2), Some(&path));
+        assert_eq!(store.load().unwrap(), 0);
+        store.change(CFG_HEARTBEAT_INTERVAL, "300").unwrap();
+        store.change(CFG_METER_VALUES_SAMPLED_DATA, "Voltage,Current.Import").unwrap();
+
+        // readonly keys are not persisted
+        store.set_value(CFG_NUMBER_OF_CONNECTORS, "4").unwrap();
+        store.set_private("availability", json!({"1": false})).unwrap();
+        let jsonc = serde_json::from_str::<Value>(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(jsonc["schema"], json!(OCPP_CONFIG_SCHEMA));
+        assert_eq!(jsonc["keys"][CFG_HEARTBEAT_INTERVAL], json!("300"));
+        assert!(jsonc["keys"].get(CFG_NUMBER_OF_CONNECTORS).is_none());
+
+        // new store reads back the file, as after a reboot
+        let store = OcppConfigStore::new(v106_core_keys(60, 2), Some(&path));
+        assert!(store.load().unwrap() > 0);
+        assert_eq!(store.get_u32(CFG_HEARTBEAT_INTERVAL).unwrap(), 300);
+        assert_eq!(
+            store.get_value(CFG_METER_VALUES_SAMPLED_DATA).unwrap(),
+            "Voltage,Current.Import"
+        );
+        assert_eq!(store.get_u32(CFG_NUMBER_OF_CONNECTORS).unwrap(), 2);
+        assert_eq!(store.get_private("availability"), Some(json!({"1": false})));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn save_atomic() {
+        let path = store_path("atomic");
+        let tmpname = format!("{}.tmp", path);
+
+        // leftover of an interrupted write is replaced, then renamed over the file
+        fs::write(&tmpname, "{truncated").unwrap();
+        let store = OcppConfigStore::new(v106_core_keys(60, 2), Some(&path));
+        store.change(CFG_CONNECTION_TIMEOUT, "90").unwrap();
+        assert!(!std::path::Path::new(&tmpname).exists());
+        let jsonc = serde_json::from_str::<Value>(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(jsonc["keys"][CFG_CONNECTION_TIMEOUT], json!("90"));
+
+        // failed write keeps previous value and returns an error to csms
+        let file = store_path("atomic-parent");
+        fs::write(&file, "").unwrap();
+        let store = OcppConfigStore::new(v106_core_keys(60, 2), Some(&format!("{}/config.json", file)));
+        assert!(store.change(CFG_CONNECTION_TIMEOUT, "90").is_err());
+        assert_eq!(store.get_u32(CFG_CONNECTION_TIMEOUT).unwrap(), 60);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(&file);
+    }
+
+    #[test]
+    fn load_ignored() {
+        let path = store_path("schema");
+
+        // other schema is refused and defaults are kept (binding only logs it)
+        let jsonc = json!({"schema": OCPP_CONFIG_SCHEMA + 1, "keys": {CFG_HEARTBEAT_INTERVAL: "300"}});
+        fs::write(&path, jsonc.to_string()).unwrap();
+        let store = OcppConfigStore::new(v106_core_keys(60, 2), Some(&path));
+        assert!(store.load().is_err());
+        assert_eq!(store.get_u32(CFG_HEARTBEAT_INTERVAL).unwrap(), 60);
+
+        // unknown, readonly and invalid values are dropped
+        let jsonc = json!({"schema": OCPP_CONFIG_SCHEMA, "keys": {
+            CFG_HEARTBEAT_INTERVAL: "300",
+            CFG_CONNECTION_TIMEOUT: "never",
+            CFG_NUMBER_OF_CONNECTORS: "8",
+            "NoSuchKey": "1",
+        }});
+        fs::write(&path, jsonc.to_string()).unwrap();
+        assert_eq!(store.load().unwrap(), 1);
+        assert_eq!(store.get_u32(CFG_HEARTBEAT_INTERVAL).unwrap(), 300);
+        assert_eq!(store.get_u32(CFG_CONNECTION_TIMEOUT).unwrap(), 60);
+        assert_eq!(store.get_u32(CFG_NUMBER_OF_CONNECTORS).unwrap(), 2);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(store.load().is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn dump_reset() {
+        let path = store_path("reset");
+        let store = OcppConfigStore::new(v106_core_keys(60, 2), Some(&path));
+        store.change(CFG_HEARTBEAT_INTERVAL, "300").unwrap();
+        store.set_private("availability", json!({"1": false})).unwrap();
+
+        let dump = store.dump().unwrap();
+        assert_eq!(dump["path"], json!(path));
+        assert_eq!(dump["private"]["availability"], json!({"1": false}));
+        let keys = dump["keys"].as_array().unwrap();
+        let key = keys.iter().find(|key| key["key"] == CFG_HEARTBEAT_INTERVAL).unwrap();
+        assert_eq!(key["value"], json!("300"));
+        assert_eq!(key["readonly"], json!(false));
+
+        // reset restores defaults and drops persisted file
+        store.reset().unwrap();
+        assert!(!std::path::Path::new(&path).exists());
+        assert_eq!(store.get_u32(CFG_HEARTBEAT_INTERVAL).unwrap(), 60);
+        let dump = store.dump().unwrap();
+        assert_eq!(dump["private"], json!({}));
+
+        // reset without file is not an error
+        store.reset().unwrap();
+    }
+}
@@ -10,7 +10,10 @@
  *
  */
 use strum_macros::Display;
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
 
 use rust_ocpp::v1_6::*;
 pub type AuthorizeRequest= messages::authorize::AuthorizeRequest;
@@ -84,8 +87,22 @@ pub type MeterValue= types::MeterValue;
 pub type UnitOfMeasure= types::UnitOfMeasure;
 pub type Measurand= types::Measurand;
 pub type ChargingProfileStatus= types::ChargingProfileStatus;
+pub type ChargingProfile= types::ChargingProfile;
+pub type ChargingProfilePurposeType= types::ChargingProfilePurposeType;
 pub type Reason= types::Reason;
 pub type RemoteStartStopStatus= types::RemoteStartStopStatus;
+pub type UnlockStatus= types::UnlockStatus;
+pub type MessageTrigger= types::MessageTrigger;
+pub type TriggerMessageStatus= types::TriggerMessageStatus;
+pub type DiagnosticsStatus= types::DiagnosticsStatus;
+pub type FirmwareStatus= types::FirmwareStatus;
+pub type IdTagInfo= types::IdTagInfo;
+pub type UpdateType= types::UpdateType;
+pub type UpdateStatus= types::UpdateStatus;
+pub type ClearCacheStatus= types::ClearCacheStatus;
+pub type KeyValue= types::KeyValue;
+pub type ConfigurationStatus= types::ConfigurationStatus;
+pub type RegistrationStatus= types::RegistrationStatus;
 
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
@@ -176,11 +193,12 @@ pub enum ChangeAvailability {
     Response(ChangeAvailabilityResponse),
 }
 
+// Warning: empty request should be declared last
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
 #[serde(untagged)]
 pub enum ClearCache {
-    Request(ClearCacheRequest),
     Response(ClearCacheResponse),
+    Request(ClearCacheRequest),
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
@@ -211,11 +229,12 @@ pub enum GetCompositeSchedule {
     Response(GetCompositeScheduleResponse),
 }
 
+// Warning: empty request should be declared last
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
 #[serde(untagged)]
 pub enum GetLocalListVersion {
-    Request(GetLocalListVersionRequest),
     Response(GetLocalListVersionResponse),
+    Request(GetLocalListVersionRequest),
 }
 
 // Warning: declare empty object and the end other wise they always match
@@ -293,6 +312,151 @@ pub enum UpdateFirmware {
     Response(UpdateFirmwareResponse),
 }
 
+// OCPP-J frames carry action name and direction, envelopes use them to decode deterministically.
+// Untagged wrappers above remain for afb transport extension (encoding only).
+ocpp_action!("Authorize", AuthorizeRequest, AuthorizeResponse);
+ocpp_action!("BootNotification", BootNotificationRequest, BootNotificationResponse);
+ocpp_action!("CancelReservation", CancelReservationRequest, CancelReservationResponse);
+ocpp_action!("ChangeAvailability", ChangeAvailabilityRequest, ChangeAvailabilityResponse);
+ocpp_action!("ChangeConfiguration", ChangeConfigurationRequest, ChangeConfigurationResponse);
+ocpp_action!("ClearCache", ClearCacheRequest, ClearCacheResponse);
+ocpp_action!("ClearChargingProfile", ClearChargingProfileRequest, ClearChargingProfileResponse);
+ocpp_action!("DataTransfer", DataTransferRequest, DataTransferResponse);
+ocpp_action!("DiagnosticsStatusNotification", DiagnosticsStatusNotificationRequest, DiagnosticsStatusNotificationResponse);
+ocpp_action!("FirmwareStatusNotification", FirmwareStatusNotificationRequest, FirmwareStatusNotificationResponse);
+ocpp_action!("GetCompositeSchedule", GetCompositeScheduleRequest, GetCompositeScheduleResponse);
+ocpp_action!("GetConfiguration", GetConfigurationRequest, GetConfigurationResponse);
+ocpp_action!("GetDiagnostics", GetDiagnosticsRequest, GetDiagnosticsResponse);
+ocpp_action!("GetLocalListVersion", GetLocalListVersionRequest, GetLocalListVersionResponse);
+ocpp_action!("Heartbeat", HeartbeatRequest, HeartbeatResponse);
+ocpp_action!("MeterValues", MeterValuesRequest, MeterValuesResponse);
+ocpp_action!("RemoteStartTransaction", RemoteStartTransactionRequest, RemoteStartTransactionResponse);
+ocpp_action!("RemoteStopTransaction", RemoteStopTransactionRequest, RemoteStopTransactionResponse);
+ocpp_action!("ReserveNow", ReserveNowRequest, ReserveNowResponse);
+ocpp_action!("Reset", ResetRequest, ResetResponse);
+ocpp_action!("SendLocalList", SendLocalListRequest, SendLocalListResponse);
+ocpp_action!("SetChargingProfile", SetChargingProfileRequest, SetChargingProfileResponse);
+ocpp_action!("StartTransaction", StartTransactionRequest, StartTransactionResponse);
+ocpp_action!("StatusNotification", StatusNotificationRequest, StatusNotificationResponse);
+ocpp_action!("StopTransaction", StopTransactionRequest, StopTransactionResponse);
+ocpp_action!("TriggerMessage", TriggerMessageRequest, TriggerMessageResponse);
+ocpp_action!("UnlockConnector", UnlockConnectorRequest, UnlockConnectorResponse);
+ocpp_action!("UpdateFirmware", UpdateFirmwareRequest, UpdateFirmwareResponse);
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
+#[serde(tag = "action", content = "payload")]
+pub enum OcppRequest {
+    Authorize(AuthorizeRequest),
+    BootNotification(BootNotificationRequest),
+    CancelReservation(CancelReservationRequest),
+    ChangeAvailability(ChangeAvailabilityRequest),
+    ChangeConfiguration(ChangeConfigurationRequest),
+    ClearCache(ClearCacheRequest),
+    ClearChargingProfile(ClearChargingProfileRequest),
+    DataTransfer(DataTransferRequest),
+    DiagnosticsStatusNotification(DiagnosticsStatusNotificationRequest),
+    FirmwareStatusNotification(FirmwareStatusNotificationRequest),
+    GetCompositeSchedule(GetCompositeScheduleRequest),
+    GetConfiguration(GetConfigurationRequest),
+    GetDiagnostics(GetDiagnosticsRequest),
+    GetLocalListVersion(GetLocalListVersionRequest),
+    Heartbeat(HeartbeatRequest),
+    MeterValues(MeterValuesRequest),
+    RemoteStartTransaction(RemoteStartTransactionRequest),
+    RemoteStopTransaction(RemoteStopTransactionRequest),
+    ReserveNow(ReserveNowRequest),
+    Reset(ResetRequest),
+    SendLocalList(SendLocalListRequest),
+    SetChargingProfile(SetChargingProfileRequest),
+    StartTransaction(StartTransactionRequest),
+    StatusNotification(StatusNotificationRequest),
+    StopTransaction(StopTransactionRequest),
+    TriggerMessage(TriggerMessageRequest),
+    UnlockConnector(UnlockConnectorRequest),
+    UpdateFirmware(UpdateFirmwareRequest),
+}
+
+impl OcppRequest {
+    pub fn decode(action: &str, payload: serde_json::Value) -> Result<Self, AfbError> {
+        let envelope = serde_json::json!({"action": action, "payload": payload});
+        match serde_json::from_value::<OcppRequest>(envelope) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("ocpp-request-decode", "action:{} error:{}", action, error),
+        }
+    }
+
+    pub fn get_action(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn get_payload(&self) -> Result<serde_json::Value, AfbError> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(mut envelope)) => match envelope.remove("payload") {
+                Some(value) => Ok(value),
+                None => Ok(serde_json::json!({})),
+            },
+            _ => afb_error!("ocpp-request-encode", "action:{} invalid payload", self),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
+#[serde(tag = "action", content = "payload")]
+pub enum OcppResponse {
+    Authorize(AuthorizeResponse),
+    BootNotification(BootNotificationResponse),
+    CancelReservation(CancelReservationResponse),
+    ChangeAvailability(ChangeAvailabilityResponse),
+    ChangeConfiguration(ChangeConfigurationResponse),
+    ClearCache(ClearCacheResponse),
+    ClearChargingProfile(ClearChargingProfileResponse),
+    DataTransfer(DataTransferResponse),
+    DiagnosticsStatusNotification(DiagnosticsStatusNotificationResponse),
+    FirmwareStatusNotification(FirmwareStatusNotificationResponse),
+    GetCompositeSchedule(GetCompositeScheduleResponse),
+    GetConfiguration(GetConfigurationResponse),
+    GetDiagnostics(GetDiagnosticsResponse),
+    GetLocalListVersion(GetLocalListVersionResponse),
+    Heartbeat(HeartbeatResponse),
+    MeterValues(MeterValuesResponse),
+    RemoteStartTransaction(RemoteStartTransactionResponse),
+    RemoteStopTransaction(RemoteStopTransactionResponse),
+    ReserveNow(ReserveNowResponse),
+    Reset(ResetResponse),
+    SendLocalList(SendLocalListResponse),
+    SetChargingProfile(SetChargingProfileResponse),
+    StartTransaction(StartTransactionResponse),
+    StatusNotification(StatusNotificationResponse),
+    StopTransaction(StopTransactionResponse),
+    TriggerMessage(TriggerMessageResponse),
+    UnlockConnector(UnlockConnectorResponse),
+    UpdateFirmware(UpdateFirmwareResponse),
+}
+
+impl OcppResponse {
+    pub fn decode(action: &str, payload: serde_json::Value) -> Result<Self, AfbError> {
+        let envelope = serde_json::json!({"action": action, "payload": payload});
+        match serde_json::from_value::<OcppResponse>(envelope) {
+            Ok(value) => Ok(value),
+            Err(error) => afb_error!("ocpp-response-decode", "action:{} error:{}", action, error),
+        }
+    }
+
+    pub fn get_action(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn get_payload(&self) -> Result<serde_json::Value, AfbError> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(mut envelope)) => match envelope.remove("payload") {
+                Some(value) => Ok(value),
+                None => Ok(serde_json::json!({})),
+            },
+            _ => afb_error!("ocpp-response-encode", "action:{} invalid payload", self),
+        }
+    }
+}
+
 // implement afb-v4 type encoder/decoder
 AfbDataConverter!(boot_notification, BootNotification);
 AfbDataConverter!(authorization, Authorize);
@@ -308,6 +472,20 @@ AfbDataConverter!(remote_reset, Reset);
 AfbDataConverter!(reserve_now, ReserveNow);
 AfbDataConverter!(meter_values, MeterValues);
 AfbDataConverter!(set_charging_profile, SetChargingProfile);
+AfbDataConverter!(get_diagnostics, GetDiagnostics);
+AfbDataConverter!(get_configuration, GetConfiguration);
+AfbDataConverter!(change_configuration, ChangeConfiguration);
+AfbDataConverter!(clear_cache, ClearCache);
+AfbDataConverter!(clear_charging_profile, ClearChargingProfile);
+AfbDataConverter!(data_transfer, DataTransfer);
+AfbDataConverter!(diagnostics_status_notification, DiagnosticsStatusNotification);
+AfbDataConverter!(firmware_status_notification, FirmwareStatusNotification);
+AfbDataConverter!(get_composite_schedule, GetCompositeSchedule);
+AfbDataConverter!(get_local_list_version, GetLocalListVersion);
+AfbDataConverter!(send_local_list, SendLocalList);
+AfbDataConverter!(trigger_message, TriggerMessage);
+AfbDataConverter!(unlock_connector, UnlockConnector);
+AfbDataConverter!(update_firmware, UpdateFirmware);
 
 pub fn register_datatype() -> Result<(),AfbError> {
     // Custom type should be registered at binding startup time
@@ -325,5 +503,19 @@ pub fn register_datatype() -> Result<(),AfbError> {
    reserve_now::register()?;
    meter_values::register()?;
    set_charging_profile::register()?;
+   get_diagnostics::register()?;
+   get_configuration::register()?;
+   change_configuration::register()?;
+   clear_cache::register()?;
+   clear_charging_profile::register()?;
+   data_transfer::register()?;
+   diagnostics_status_notification::register()?;
+   firmware_status_notification::register()?;
+   get_composite_schedule::register()?;
+   get_local_list_version::register()?;
+   send_local_list::register()?;
+   trigger_message::register()?;
+   unlock_connector::register()?;
+   update_firmware::register()?;
    Ok(())
 }
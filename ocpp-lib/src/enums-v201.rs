@@ -9,138 +9,181 @@
  *   http://www.apache.org/licenses/LICENSE-2.0
  *
  */
+#[cfg(feature = "afbv4")]
+use afbv4::prelude::*;
 use std::fmt;
 use std::str::FromStr;
 use strum_macros::Display;
-
-use rust_ocpp::v2_0_1::messages::authorize::AuthorizeRequest;
-use rust_ocpp::v2_0_1::messages::authorize::AuthorizeResponse;
-use rust_ocpp::v2_0_1::messages::boot_notification::BootNotificationRequest;
-use rust_ocpp::v2_0_1::messages::boot_notification::BootNotificationResponse;
-use rust_ocpp::v2_0_1::messages::cancel_reservation::CancelReservationRequest;
-use rust_ocpp::v2_0_1::messages::cancel_reservation::CancelReservationResponse;
-use rust_ocpp::v2_0_1::messages::certificate_signed::CertificateSignedRequest;
-use rust_ocpp::v2_0_1::messages::certificate_signed::CertificateSignedResponse;
-use rust_ocpp::v2_0_1::messages::change_availability::ChangeAvailabilityRequest;
-use rust_ocpp::v2_0_1::messages::change_availability::ChangeAvailabilityResponse;
-use rust_ocpp::v2_0_1::messages::clear_cache::ClearCacheRequest;
-use rust_ocpp::v2_0_1::messages::clear_cache::ClearCacheResponse;
-use rust_ocpp::v2_0_1::messages::clear_charging_profile::ClearChargingProfileRequest;
-use rust_ocpp::v2_0_1::messages::clear_charging_profile::ClearChargingProfileResponse;
-use rust_ocpp::v2_0_1::messages::clear_display_message::ClearDisplayMessageRequest;
-use rust_ocpp::v2_0_1::messages::clear_display_message::ClearDisplayMessageResponse;
-use rust_ocpp::v2_0_1::messages::clear_variable_monitoring::ClearVariableMonitoringRequest;
-use rust_ocpp::v2_0_1::messages::clear_variable_monitoring::ClearVariableMonitoringResponse;
-use rust_ocpp::v2_0_1::messages::cleared_charging_limit::ClearedChargingLimitRequest;
-use rust_ocpp::v2_0_1::messages::cleared_charging_limit::ClearedChargingLimitResponse;
-use rust_ocpp::v2_0_1::messages::cost_updated::CostUpdatedRequest;
-use rust_ocpp::v2_0_1::messages::cost_updated::CostUpdatedResponse;
-use rust_ocpp::v2_0_1::messages::customer_information::CustomerInformationRequest;
-use rust_ocpp::v2_0_1::messages::customer_information::CustomerInformationResponse;
-use rust_ocpp::v2_0_1::messages::datatransfer::DataTransferRequest;
-use rust_ocpp::v2_0_1::messages::datatransfer::DataTransferResponse;
-use rust_ocpp::v2_0_1::messages::delete_certificate::DeleteCertificateRequest;
-use rust_ocpp::v2_0_1::messages::delete_certificate::DeleteCertificateResponse;
-use rust_ocpp::v2_0_1::messages::firmware_status_notification::FirmwareStatusNotificationRequest;
-use rust_ocpp::v2_0_1::messages::firmware_status_notification::FirmwareStatusNotificationResponse;
-use rust_ocpp::v2_0_1::messages::get_15118ev_certificate::Get15118EVCertificateRequest;
-use rust_ocpp::v2_0_1::messages::get_15118ev_certificate::Get15118EVCertificateResponse;
-use rust_ocpp::v2_0_1::messages::get_base_report::GetBaseReportRequest;
-use rust_ocpp::v2_0_1::messages::get_base_report::GetBaseReportResponse;
-use rust_ocpp::v2_0_1::messages::get_certificate_status::GetCertificateStatusRequest;
-use rust_ocpp::v2_0_1::messages::get_certificate_status::GetCertificateStatusResponse;
-use rust_ocpp::v2_0_1::messages::get_charging_profiles::GetChargingProfilesRequest;
-use rust_ocpp::v2_0_1::messages::get_charging_profiles::GetChargingProfilesResponse;
-use rust_ocpp::v2_0_1::messages::get_composite_schedule::GetCompositeScheduleRequest;
-use rust_ocpp::v2_0_1::messages::get_composite_schedule::GetCompositeScheduleResponse;
-use rust_ocpp::v2_0_1::messages::get_display_message::GetDisplayMessagesRequest;
-use rust_ocpp::v2_0_1::messages::get_display_message::GetDisplayMessagesResponse;
-use rust_ocpp::v2_0_1::messages::get_installed_certificate_ids::GetInstalledCertificateIdsRequest;
-use rust_ocpp::v2_0_1::messages::get_installed_certificate_ids::GetInstalledCertificateIdsResponse;
-use rust_ocpp::v2_0_1::messages::get_local_list_version::GetLocalListVersionRequest;
-use rust_ocpp::v2_0_1::messages::get_local_list_version::GetLocalListVersionResponse;
-use rust_ocpp::v2_0_1::messages::get_log::GetLogRequest;
-use rust_ocpp::v2_0_1::messages::get_log::GetLogResponse;
-use rust_ocpp::v2_0_1::messages::get_monitoring_report::GetMonitoringReportRequest;
-use rust_ocpp::v2_0_1::messages::get_monitoring_report::GetMonitoringReportResponse;
-use rust_ocpp::v2_0_1::messages::get_report::GetReportRequest;
-use rust_ocpp::v2_0_1::messages::get_report::GetReportResponse;
-use rust_ocpp::v2_0_1::messages::get_transaction_status::GetTransactionStatusRequest;
-use rust_ocpp::v2_0_1::messages::get_transaction_status::GetTransactionStatusResponse;
-use rust_ocpp::v2_0_1::messages::get_variables::GetVariablesRequest;
-use rust_ocpp::v2_0_1::messages::get_variables::GetVariablesResponse;
-use rust_ocpp::v2_0_1::messages::heartbeat::HeartbeatRequest;
-use rust_ocpp::v2_0_1::messages::heartbeat::HeartbeatResponse;
-use rust_ocpp::v2_0_1::messages::install_certificate::InstallCertificateRequest;
-use rust_ocpp::v2_0_1::messages::install_certificate::InstallCertificateResponse;
-use rust_ocpp::v2_0_1::messages::log_status_notification::LogStatusNotificationRequest;
-use rust_ocpp::v2_0_1::messages::log_status_notification::LogStatusNotificationResponse;
-use rust_ocpp::v2_0_1::messages::meter_values::MeterValuesRequest;
-use rust_ocpp::v2_0_1::messages::meter_values::MeterValuesResponse;
-use rust_ocpp::v2_0_1::messages::notify_charging_limit::NotifyChargingLimitRequest;
-use rust_ocpp::v2_0_1::messages::notify_charging_limit::NotifyChargingLimitResponse;
-use rust_ocpp::v2_0_1::messages::notify_customer_information::NotifyCustomerInformationRequest;
-use rust_ocpp::v2_0_1::messages::notify_customer_information::NotifyCustomerInformationResponse;
-use rust_ocpp::v2_0_1::messages::notify_display_messages::NotifyDisplayMessagesRequest;
-use rust_ocpp::v2_0_1::messages::notify_display_messages::NotifyDisplayMessagesResponse;
-use rust_ocpp::v2_0_1::messages::notify_ev_charging_needs::NotifyEVChargingNeedsRequest;
-use rust_ocpp::v2_0_1::messages::notify_ev_charging_needs::NotifyEVChargingNeedsResponse;
-use rust_ocpp::v2_0_1::messages::notify_ev_charging_schedule::NotifyEVChargingScheduleRequest;
-use rust_ocpp::v2_0_1::messages::notify_ev_charging_schedule::NotifyEVChargingScheduleResponse;
-use rust_ocpp::v2_0_1::messages::notify_event::NotifyEventRequest;
-use rust_ocpp::v2_0_1::messages::notify_event::NotifyEventResponse;
-use rust_ocpp::v2_0_1::messages::notify_monitoring_report::NotifyMonitoringReportRequest;
-use rust_ocpp::v2_0_1::messages::notify_monitoring_report::NotifyMonitoringReportResponse;
-use rust_ocpp::v2_0_1::messages::notify_report::NotifyReportRequest;
-use rust_ocpp::v2_0_1::messages::notify_report::NotifyReportResponse;
-use rust_ocpp::v2_0_1::messages::publish_firmware::PublishFirmwareRequest;
-use rust_ocpp::v2_0_1::messages::publish_firmware::PublishFirmwareResponse;
-use rust_ocpp::v2_0_1::messages::publish_firmware_status_notification::PublishFirmwareStatusNotificationRequest;
-use rust_ocpp::v2_0_1::messages::publish_firmware_status_notification::PublishFirmwareStatusNotificationResponse;
-use rust_ocpp::v2_0_1::messages::report_charging_profiles::ReportChargingProfilesRequest;
-use rust_ocpp::v2_0_1::messages::report_charging_profiles::ReportChargingProfilesResponse;
-use rust_ocpp::v2_0_1::messages::request_start_transaction::RequestStartTransactionRequest as StartTransactionRequest;
-use rust_ocpp::v2_0_1::messages::request_start_transaction::RequestStartTransactionResponse as StartTransactionResponse;
-use rust_ocpp::v2_0_1::messages::request_stop_transaction::RequestStopTransactionRequest as StopTransactionRequest;
-use rust_ocpp::v2_0_1::messages::request_stop_transaction::RequestStopTransactionResponse as StopTransactionResponse;
-use rust_ocpp::v2_0_1::messages::reservation_status_update::ReservationStatusUpdateRequest;
-use rust_ocpp::v2_0_1::messages::reservation_status_update::ReservationStatusUpdateResponse;
-use rust_ocpp::v2_0_1::messages::reserve_now::ReserveNowRequest;
-use rust_ocpp::v2_0_1::messages::reserve_now::ReserveNowResponse;
-use rust_ocpp::v2_0_1::messages::reset::ResetRequest;
-use rust_ocpp::v2_0_1::messages::reset::ResetResponse;
-use rust_ocpp::v2_0_1::messages::security_event_notification::SecurityEventNotificationRequest;
-use rust_ocpp::v2_0_1::messages::security_event_notification::SecurityEventNotificationResponse;
-use rust_ocpp::v2_0_1::messages::send_local_list::SendLocalListRequest;
-use rust_ocpp::v2_0_1::messages::send_local_list::SendLocalListResponse;
-use rust_ocpp::v2_0_1::messages::set_charging_profile::SetChargingProfileRequest;
-use rust_ocpp::v2_0_1::messages::set_charging_profile::SetChargingProfileResponse;
-use rust_ocpp::v2_0_1::messages::set_display_message::SetDisplayMessageRequest;
-use rust_ocpp::v2_0_1::messages::set_display_message::SetDisplayMessageResponse;
-use rust_ocpp::v2_0_1::messages::set_monitoring_base::SetMonitoringBaseRequest;
-use rust_ocpp::v2_0_1::messages::set_monitoring_base::SetMonitoringBaseResponse;
-use rust_ocpp::v2_0_1::messages::set_monitoring_level::SetMonitoringLevelRequest;
-use rust_ocpp::v2_0_1::messages::set_monitoring_level::SetMonitoringLevelResponse;
-use rust_ocpp::v2_0_1::messages::set_network_profile::SetNetworkProfileRequest;
-use rust_ocpp::v2_0_1::messages::set_network_profile::SetNetworkProfileResponse;
-use rust_ocpp::v2_0_1::messages::set_variable_monitoring::SetVariableMonitoringRequest;
-use rust_ocpp::v2_0_1::messages::set_variable_monitoring::SetVariableMonitoringResponse;
-use rust_ocpp::v2_0_1::messages::set_variables::SetVariablesRequest;
-use rust_ocpp::v2_0_1::messages::set_variables::SetVariablesResponse;
-use rust_ocpp::v2_0_1::messages::sign_certificate::SignCertificateRequest;
-use rust_ocpp::v2_0_1::messages::sign_certificate::SignCertificateResponse;
-use rust_ocpp::v2_0_1::messages::status_notification::StatusNotificationRequest;
-use rust_ocpp::v2_0_1::messages::status_notification::StatusNotificationResponse;
-use rust_ocpp::v2_0_1::messages::transaction_event::TransactionEventRequest;
-use rust_ocpp::v2_0_1::messages::transaction_event::TransactionEventResponse;
-use rust_ocpp::v2_0_1::messages::trigger_message::TriggerMessageRequest;
-use rust_ocpp::v2_0_1::messages::trigger_message::TriggerMessageResponse;
-use rust_ocpp::v2_0_1::messages::unlock_connector::UnlockConnectorRequest;
-use rust_ocpp::v2_0_1::messages::unlock_connector::UnlockConnectorResponse;
-use rust_ocpp::v2_0_1::messages::unpublish_firmware::UnpublishFirmwareRequest;
-use rust_ocpp::v2_0_1::messages::unpublish_firmware::UnpublishFirmwareResponse;
-use rust_ocpp::v2_0_1::messages::update_firmware::UpdateFirmwareRequest;
-use rust_ocpp::v2_0_1::messages::update_firmware::UpdateFirmwareResponse;
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+
+pub use rust_ocpp::v2_0_1::messages::authorize::AuthorizeRequest;
+pub use rust_ocpp::v2_0_1::messages::authorize::AuthorizeResponse;
+pub use rust_ocpp::v2_0_1::messages::boot_notification::BootNotificationRequest;
+pub use rust_ocpp::v2_0_1::messages::boot_notification::BootNotificationResponse;
+pub use rust_ocpp::v2_0_1::messages::cancel_reservation::CancelReservationRequest;
+pub use rust_ocpp::v2_0_1::messages::cancel_reservation::CancelReservationResponse;
+pub use rust_ocpp::v2_0_1::messages::certificate_signed::CertificateSignedRequest;
+pub use rust_ocpp::v2_0_1::messages::certificate_signed::CertificateSignedResponse;
+pub use rust_ocpp::v2_0_1::messages::change_availability::ChangeAvailabilityRequest;
+pub use rust_ocpp::v2_0_1::messages::change_availability::ChangeAvailabilityResponse;
+pub use rust_ocpp::v2_0_1::messages::clear_cache::ClearCacheRequest;
+pub use rust_ocpp::v2_0_1::messages::clear_cache::ClearCacheResponse;
+pub use rust_ocpp::v2_0_1::messages::clear_charging_profile::ClearChargingProfileRequest;
+pub use rust_ocpp::v2_0_1::messages::clear_charging_profile::ClearChargingProfileResponse;
+pub use rust_ocpp::v2_0_1::messages::clear_display_message::ClearDisplayMessageRequest;
+pub use rust_ocpp::v2_0_1::messages::clear_display_message::ClearDisplayMessageResponse;
+pub use rust_ocpp::v2_0_1::messages::clear_variable_monitoring::ClearVariableMonitoringRequest;
+pub use rust_ocpp::v2_0_1::messages::clear_variable_monitoring::ClearVariableMonitoringResponse;
+pub use rust_ocpp::v2_0_1::messages::cleared_charging_limit::ClearedChargingLimitRequest;
+pub use rust_ocpp::v2_0_1::messages::cleared_charging_limit::ClearedChargingLimitResponse;
+pub use rust_ocpp::v2_0_1::messages::cost_updated::CostUpdatedRequest;
+pub use rust_ocpp::v2_0_1::messages::cost_updated::CostUpdatedResponse;
+pub use rust_ocpp::v2_0_1::messages::customer_information::CustomerInformationRequest;
+pub use rust_ocpp::v2_0_1::messages::customer_information::CustomerInformationResponse;
+pub use rust_ocpp::v2_0_1::messages::datatransfer::DataTransferRequest;
+pub use rust_ocpp::v2_0_1::messages::datatransfer::DataTransferResponse;
+pub use rust_ocpp::v2_0_1::messages::delete_certificate::DeleteCertificateRequest;
+pub use rust_ocpp::v2_0_1::messages::delete_certificate::DeleteCertificateResponse;
+pub use rust_ocpp::v2_0_1::messages::firmware_status_notification::FirmwareStatusNotificationRequest;
+pub use rust_ocpp::v2_0_1::messages::firmware_status_notification::FirmwareStatusNotificationResponse;
+pub use rust_ocpp::v2_0_1::messages::get_15118ev_certificate::Get15118EVCertificateRequest;
+pub use rust_ocpp::v2_0_1::messages::get_15118ev_certificate::Get15118EVCertificateResponse;
+pub use rust_ocpp::v2_0_1::messages::get_base_report::GetBaseReportRequest;
+pub use rust_ocpp::v2_0_1::messages::get_base_report::GetBaseReportResponse;
+pub use rust_ocpp::v2_0_1::messages::get_certificate_status::GetCertificateStatusRequest;
+pub use rust_ocpp::v2_0_1::messages::get_certificate_status::GetCertificateStatusResponse;
+pub use rust_ocpp::v2_0_1::messages::get_charging_profiles::GetChargingProfilesRequest;
+pub use rust_ocpp::v2_0_1::messages::get_charging_profiles::GetChargingProfilesResponse;
+pub use rust_ocpp::v2_0_1::messages::get_composite_schedule::GetCompositeScheduleRequest;
+pub use rust_ocpp::v2_0_1::messages::get_composite_schedule::GetCompositeScheduleResponse;
+pub use rust_ocpp::v2_0_1::messages::get_display_message::GetDisplayMessagesRequest;
+pub use rust_ocpp::v2_0_1::messages::get_display_message::GetDisplayMessagesResponse;
+pub use rust_ocpp::v2_0_1::messages::get_installed_certificate_ids::GetInstalledCertificateIdsRequest;
+pub use rust_ocpp::v2_0_1::messages::get_installed_certificate_ids::GetInstalledCertificateIdsResponse;
+pub use rust_ocpp::v2_0_1::messages::get_local_list_version::GetLocalListVersionRequest;
+pub use rust_ocpp::v2_0_1::messages::get_local_list_version::GetLocalListVersionResponse;
+pub use rust_ocpp::v2_0_1::messages::get_log::GetLogRequest;
+pub use rust_ocpp::v2_0_1::messages::get_log::GetLogResponse;
+pub use rust_ocpp::v2_0_1::messages::get_monitoring_report::GetMonitoringReportRequest;
+pub use rust_ocpp::v2_0_1::messages::get_monitoring_report::GetMonitoringReportResponse;
+pub use rust_ocpp::v2_0_1::messages::get_report::GetReportRequest;
+pub use rust_ocpp::v2_0_1::messages::get_report::GetReportResponse;
+pub use rust_ocpp::v2_0_1::messages::get_transaction_status::GetTransactionStatusRequest;
+pub use rust_ocpp::v2_0_1::messages::get_transaction_status::GetTransactionStatusResponse;
+pub use rust_ocpp::v2_0_1::messages::get_variables::GetVariablesRequest;
+pub use rust_ocpp::v2_0_1::messages::get_variables::GetVariablesResponse;
+pub use rust_ocpp::v2_0_1::messages::heartbeat::HeartbeatRequest;
+pub use rust_ocpp::v2_0_1::messages::heartbeat::HeartbeatResponse;
+pub use rust_ocpp::v2_0_1::messages::install_certificate::InstallCertificateRequest;
+pub use rust_ocpp::v2_0_1::messages::install_certificate::InstallCertificateResponse;
+pub use rust_ocpp::v2_0_1::messages::log_status_notification::LogStatusNotificationRequest;
+pub use rust_ocpp::v2_0_1::messages::log_status_notification::LogStatusNotificationResponse;
+pub use rust_ocpp::v2_0_1::messages::meter_values::MeterValuesRequest;
+pub use rust_ocpp::v2_0_1::messages::meter_values::MeterValuesResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_charging_limit::NotifyChargingLimitRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_charging_limit::NotifyChargingLimitResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_customer_information::NotifyCustomerInformationRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_customer_information::NotifyCustomerInformationResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_display_messages::NotifyDisplayMessagesRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_display_messages::NotifyDisplayMessagesResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_ev_charging_needs::NotifyEVChargingNeedsRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_ev_charging_needs::NotifyEVChargingNeedsResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_ev_charging_schedule::NotifyEVChargingScheduleRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_ev_charging_schedule::NotifyEVChargingScheduleResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_event::NotifyEventRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_event::NotifyEventResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_monitoring_report::NotifyMonitoringReportRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_monitoring_report::NotifyMonitoringReportResponse;
+pub use rust_ocpp::v2_0_1::messages::notify_report::NotifyReportRequest;
+pub use rust_ocpp::v2_0_1::messages::notify_report::NotifyReportResponse;
+pub use rust_ocpp::v2_0_1::messages::publish_firmware::PublishFirmwareRequest;
+pub use rust_ocpp::v2_0_1::messages::publish_firmware::PublishFirmwareResponse;
+pub use rust_ocpp::v2_0_1::messages::publish_firmware_status_notification::PublishFirmwareStatusNotificationRequest;
+pub use rust_ocpp::v2_0_1::messages::publish_firmware_status_notification::PublishFirmwareStatusNotificationResponse;
+pub use rust_ocpp::v2_0_1::messages::report_charging_profiles::ReportChargingProfilesRequest;
+pub use rust_ocpp::v2_0_1::messages::report_charging_profiles::ReportChargingProfilesResponse;
+pub use rust_ocpp::v2_0_1::messages::request_start_transaction::RequestStartTransactionRequest as StartTransactionRequest;
+pub use rust_ocpp::v2_0_1::messages::request_start_transaction::RequestStartTransactionResponse as StartTransactionResponse;
+pub use rust_ocpp::v2_0_1::messages::request_stop_transaction::RequestStopTransactionRequest as StopTransactionRequest;
+pub use rust_ocpp::v2_0_1::messages::request_stop_transaction::RequestStopTransactionResponse as StopTransactionResponse;
+pub use rust_ocpp::v2_0_1::messages::reservation_status_update::ReservationStatusUpdateRequest;
+pub use rust_ocpp::v2_0_1::messages::reservation_status_update::ReservationStatusUpdateResponse;
+pub use rust_ocpp::v2_0_1::messages::reserve_now::ReserveNowRequest;
+pub use rust_ocpp::v2_0_1::messages::reserve_now::ReserveNowResponse;
+pub use rust_ocpp::v2_0_1::messages::reset::ResetRequest;
+pub use rust_ocpp::v2_0_1::messages::reset::ResetResponse;
+pub use rust_ocpp::v2_0_1::messages::security_event_notification::SecurityEventNotificationRequest;
+pub use rust_ocpp::v2_0_1::messages::security_event_notification::SecurityEventNotificationResponse;
+pub use rust_ocpp::v2_0_1::messages::send_local_list::SendLocalListRequest;
+pub use rust_ocpp::v2_0_1::messages::send_local_list::SendLocalListResponse;
+pub use rust_ocpp::v2_0_1::messages::set_charging_profile::SetChargingProfileRequest;
+pub use rust_ocpp::v2_0_1::messages::set_charging_profile::SetChargingProfileResponse;
+pub use rust_ocpp::v2_0_1::messages::set_display_message::SetDisplayMessageRequest;
+pub use rust_ocpp::v2_0_1::messages::set_display_message::SetDisplayMessageResponse;
+pub use rust_ocpp::v2_0_1::messages::set_monitoring_base::SetMonitoringBaseRequest;
+pub use rust_ocpp::v2_0_1::messages::set_monitoring_base::SetMonitoringBaseResponse;
+pub use rust_ocpp::v2_0_1::messages::set_monitoring_level::SetMonitoringLevelRequest;
+pub use rust_ocpp::v2_0_1::messages::set_monitoring_level::SetMonitoringLevelResponse;
+pub use rust_ocpp::v2_0_1::messages::set_network_profile::SetNetworkProfileRequest;
+pub use rust_ocpp::v2_0_1::messages::set_network_profile::SetNetworkProfileResponse;
+pub use rust_ocpp::v2_0_1::messages::set_variable_monitoring::SetVariableMonitoringRequest;
+pub use rust_ocpp::v2_0_1::messages::set_variable_monitoring::SetVariableMonitoringResponse;
+pub use rust_ocpp::v2_0_1::messages::set_variables::SetVariablesRequest;
+pub use rust_ocpp::v2_0_1::messages::set_variables::SetVariablesResponse;
+pub use rust_ocpp::v2_0_1::messages::sign_certificate::SignCertificateRequest;
+pub use rust_ocpp::v2_0_1::messages::sign_certificate::SignCertificateResponse;
+pub use rust_ocpp::v2_0_1::messages::status_notification::StatusNotificationRequest;
+pub use rust_ocpp::v2_0_1::messages::status_notification::StatusNotificationResponse;
+pub use rust_ocpp::v2_0_1::messages::transaction_event::TransactionEventRequest;
+pub use rust_ocpp::v2_0_1::messages::transaction_event::TransactionEventResponse;
+pub use rust_ocpp::v2_0_1::messages::trigger_message::TriggerMessageRequest;
+pub use rust_ocpp::v2_0_1::messages::trigger_message::TriggerMessageResponse;
+pub use rust_ocpp::v2_0_1::messages::unlock_connector::UnlockConnectorRequest;
+pub use rust_ocpp::v2_0_1::messages::unlock_connector::UnlockConnectorResponse;
+pub use rust_ocpp::v2_0_1::messages::unpublish_firmware::UnpublishFirmwareRequest;
+pub use rust_ocpp::v2_0_1::messages::unpublish_firmware::UnpublishFirmwareResponse;
+pub use rust_ocpp::v2_0_1::messages::update_firmware::UpdateFirmwareRequest;
+pub use rust_ocpp::v2_0_1::messages::update_firmware::UpdateFirmwareResponse;
+
+use rust_ocpp::v2_0_1::datatypes;
+use rust_ocpp::v2_0_1::enumerations;
+pub type ChargingStationType = datatypes::charging_station_type::ChargingStationType;
+pub type ModemType = datatypes::modem_type::ModemType;
+pub type IdTokenType = datatypes::id_token_type::IdTokenType;
+pub type IdTokenInfoType = datatypes::id_token_info_type::IdTokenInfoType;
+pub type TransactionType = datatypes::transaction_type::TransactionType;
+pub type EVSEType = datatypes::evse_type::EVSEType;
+pub type MeterValueType = datatypes::meter_value_type::MeterValueType;
+pub type SampledValueType = datatypes::sampled_value_type::SampledValueType;
+pub type StatusInfoType = datatypes::status_info_type::StatusInfoType;
+pub type BootReasonEnumType = enumerations::boot_reason_enum_type::BootReasonEnumType;
+pub type RegistrationStatusEnumType =
+    enumerations::registration_status_enum_type::RegistrationStatusEnumType;
+pub type ConnectorStatusEnumType =
+    enumerations::connector_status_enum_type::ConnectorStatusEnumType;
+pub type IdTokenEnumType = enumerations::id_token_enum_type::IdTokenEnumType;
+pub type AuthorizationStatusEnumType =
+    enumerations::authorization_status_enum_type::AuthorizationStatusEnumType;
+pub type TransactionEventEnumType =
+    enumerations::transaction_event_enum_type::TransactionEventEnumType;
+pub type TriggerReasonEnumType = enumerations::trigger_reason_enum_type::TriggerReasonEnumType;
+pub type ChargingStateEnumType = enumerations::charging_state_enum_type::ChargingStateEnumType;
+pub type ReasonEnumType = enumerations::reason_enum_type::ReasonEnumType;
+pub type CancelReservationStatusEnumType =
+    enumerations::cancel_reservation_status_enum_type::CancelReservationStatusEnumType;
+pub type ReserveNowStatusEnumType =
+    enumerations::reserve_now_status_enum_type::ReserveNowStatusEnumType;
+pub type ResetEnumType = enumerations::reset_enum_type::ResetEnumType;
+pub type ResetStatusEnumType = enumerations::reset_status_enum_type::ResetStatusEnumType;
+pub type RequestStartStopStatusEnumType =
+    enumerations::request_start_stop_status_enum_type::RequestStartStopStatusEnumType;
+pub type ChargingProfileStatusEnumType =
+    enumerations::charging_profile_status_enum_type::ChargingProfileStatusEnumType;
+pub type OperationalStatusEnumType =
+    enumerations::operational_status_enum_type::OperationalStatusEnumType;
+pub type ChangeAvailabilityStatusEnumType =
+    enumerations::change_availability_status_enum_type::ChangeAvailabilityStatusEnumType;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
 #[serde(untagged)]
@@ -339,11 +382,12 @@ pub enum GetVariablesKind {
     Response(GetVariablesResponse),
 }
 
+// Warning: declare empty object and the end other wise they always match
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
 #[serde(untagged)]
 pub enum HeartbeatKind {
-    Request(HeartbeatRequest),
     Response(HeartbeatResponse),
+    Request(HeartbeatRequest),
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
@@ -592,8 +636,419 @@ pub enum UpdateFirmwareKind {
     Response(UpdateFirmwareResponse),
 }
 
+// bind payloads to OCPP-J action name (spec naming e.g. GetChargingProfiles)
+ocpp_action!("Authorize", AuthorizeRequest, AuthorizeResponse);
+ocpp_action!(
+    "BootNotification",
+    BootNotificationRequest,
+    BootNotificationResponse
+);
+ocpp_action!(
+    "CancelReservation",
+    CancelReservationRequest,
+    CancelReservationResponse
+);
+ocpp_action!(
+    "CertificateSigned",
+    CertificateSignedRequest,
+    CertificateSignedResponse
+);
+ocpp_action!(
+    "ChangeAvailability",
+    ChangeAvailabilityRequest,
+    ChangeAvailabilityResponse
+);
+ocpp_action!("ClearCache", ClearCacheRequest, ClearCacheResponse);
+ocpp_action!(
+    "ClearChargingProfile",
+    ClearChargingProfileRequest,
+    ClearChargingProfileResponse
+);
+ocpp_action!(
+    "ClearDisplayMessage",
+    ClearDisplayMessageRequest,
+    ClearDisplayMessageResponse
+);
+ocpp_action!(
+    "ClearedChargingLimit",
+    ClearedChargingLimitRequest,
+    ClearedChargingLimitResponse
+);
+ocpp_action!(
+    "ClearVariableMonitoring",
+    ClearVariableMonitoringRequest,
+    ClearVariableMonitoringResponse
+);
+ocpp_action!("CostUpdated", CostUpdatedRequest, CostUpdatedResponse);
+ocpp_action!(
+    "CustomerInformation",
+    CustomerInformationRequest,
+    CustomerInformationResponse
+);
+ocpp_action!("DataTransfer", DataTransferRequest, DataTransferResponse);
+ocpp_action!(
+    "DeleteCertificate",
+    DeleteCertificateRequest,
+    DeleteCertificateResponse
+);
+ocpp_action!(
+    "FirmwareStatusNotification",
+    FirmwareStatusNotificationRequest,
+    FirmwareStatusNotificationResponse
+);
+ocpp_action!(
+    "Get15118EVCertificate",
+    Get15118EVCertificateRequest,
+    Get15118EVCertificateResponse
+);
+ocpp_action!("GetBaseReport", GetBaseReportRequest, GetBaseReportResponse);
+ocpp_action!(
+    "GetCertificateStatus",
+    GetCertificateStatusRequest,
+    GetCertificateStatusResponse
+);
+ocpp_action!(
+    "GetChargingProfiles",
+    GetChargingProfilesRequest,
+    GetChargingProfilesResponse
+);
+ocpp_action!(
+    "GetCompositeSchedule",
+    GetCompositeScheduleRequest,
+    GetCompositeScheduleResponse
+);
+ocpp_action!(
+    "GetDisplayMessages",
+    GetDisplayMessagesRequest,
+    GetDisplayMessagesResponse
+);
+ocpp_action!(
+    "GetInstalledCertificateIds",
+    GetInstalledCertificateIdsRequest,
+    GetInstalledCertificateIdsResponse
+);
+ocpp_action!(
+    "GetLocalListVersion",
+    GetLocalListVersionRequest,
+    GetLocalListVersionResponse
+);
+ocpp_action!("GetLog", GetLogRequest, GetLogResponse);
+ocpp_action!(
+    "GetMonitoringReport",
+    GetMonitoringReportRequest,
+    GetMonitoringReportResponse
+);
+ocpp_action!("GetReport", GetReportRequest, GetReportResponse);
+ocpp_action!(
+    "GetTransactionStatus",
+    GetTransactionStatusRequest,
+    GetTransactionStatusResponse
+);
+ocpp_action!("GetVariables", GetVariablesRequest, GetVariablesResponse);
+ocpp_action!("Heartbeat", HeartbeatRequest, HeartbeatResponse);
+ocpp_action!(
+    "InstallCertificate",
+    InstallCertificateRequest,
+    InstallCertificateResponse
+);
+ocpp_action!(
+    "LogStatusNotification",
+    LogStatusNotificationRequest,
+    LogStatusNotificationResponse
+);
+ocpp_action!("MeterValues", MeterValuesRequest, MeterValuesResponse);
+ocpp_action!(
+    "NotifyChargingLimit",
+    NotifyChargingLimitRequest,
+    NotifyChargingLimitResponse
+);
+ocpp_action!(
+    "NotifyCustomerInformation",
+    NotifyCustomerInformationRequest,
+    NotifyCustomerInformationResponse
+);
+ocpp_action!(
+    "NotifyDisplayMessages",
+    NotifyDisplayMessagesRequest,
+    NotifyDisplayMessagesResponse
+);
+ocpp_action!(
+    "NotifyEVChargingNeeds",
+    NotifyEVChargingNeedsRequest,
+    NotifyEVChargingNeedsResponse
+);
+ocpp_action!(
+    "NotifyEVChargingSchedule",
+    NotifyEVChargingScheduleRequest,
+    NotifyEVChargingScheduleResponse
+);
+ocpp_action!("NotifyEvent", NotifyEventRequest, NotifyEventResponse);
+ocpp_action!(
+    "NotifyMonitoringReport",
+    NotifyMonitoringReportRequest,
+    NotifyMonitoringReportResponse
+);
+ocpp_action!("NotifyReport", NotifyReportRequest, NotifyReportResponse);
+ocpp_action!(
+    "PublishFirmware",
+    PublishFirmwareRequest,
+    PublishFirmwareResponse
+);
+ocpp_action!(
+    "PublishFirmwareStatusNotification",
+    PublishFirmwareStatusNotificationRequest,
+    PublishFirmwareStatusNotificationResponse
+);
+ocpp_action!(
+    "ReportChargingProfiles",
+    ReportChargingProfilesRequest,
+    ReportChargingProfilesResponse
+);
+ocpp_action!(
+    "RequestStartTransaction",
+    StartTransactionRequest,
+    StartTransactionResponse
+);
+ocpp_action!(
+    "RequestStopTransaction",
+    StopTransactionRequest,
+    StopTransactionResponse
+);
+ocpp_action!(
+    "ReservationStatusUpdate",
+    ReservationStatusUpdateRequest,
+    ReservationStatusUpdateResponse
+);
+ocpp_action!("ReserveNow", ReserveNowRequest, ReserveNowResponse);
+ocpp_action!("Reset", ResetRequest, ResetResponse);
+ocpp_action!(
+    "SecurityEventNotification",
+    SecurityEventNotificationRequest,
+    SecurityEventNotificationResponse
+);
+ocpp_action!("SendLocalList", SendLocalListRequest, SendLocalListResponse);
+ocpp_action!(
+    "SetChargingProfile",
+    SetChargingProfileRequest,
+    SetChargingProfileResponse
+);
+ocpp_action!(
+    "SetDisplayMessage",
+    SetDisplayMessageRequest,
+    SetDisplayMessageResponse
+);
+ocpp_action!(
+    "SetMonitoringBase",
+    SetMonitoringBaseRequest,
+    SetMonitoringBaseResponse
+);
+ocpp_action!(
+    "SetMonitoringLevel",
+    SetMonitoringLevelRequest,
+    SetMonitoringLevelResponse
+);
+ocpp_action!(
+    "SetNetworkProfile",
+    SetNetworkProfileRequest,
+    SetNetworkProfileResponse
+);
+ocpp_action!(
+    "SetVariableMonitoring",
+    SetVariableMonitoringRequest,
+    SetVariableMonitoringResponse
+);
+ocpp_action!("SetVariables", SetVariablesRequest, SetVariablesResponse);
+ocpp_action!(
+    "SignCertificate",
+    SignCertificateRequest,
+    SignCertificateResponse
+);
+ocpp_action!(
+    "StatusNotification",
+    StatusNotificationRequest,
+    StatusNotificationResponse
+);
+ocpp_action!(
+    "TransactionEvent",
+    TransactionEventRequest,
+    TransactionEventResponse
+);
+ocpp_action!(
+    "TriggerMessage",
+    TriggerMessageRequest,
+    TriggerMessageResponse
+);
+ocpp_action!(
+    "UnlockConnector",
+    UnlockConnectorRequest,
+    UnlockConnectorResponse
+);
+ocpp_action!(
+    "UnpublishFirmware",
+    UnpublishFirmwareRequest,
+    UnpublishFirmwareResponse
+);
+ocpp_action!(
+    "UpdateFirmware",
+    UpdateFirmwareRequest,
+    UpdateFirmwareResponse
+);
+
+// implement afb-v4 type encoder/decoder (suffix avoid name clash with v106 types)
+AfbDataConverter!(authorization_v201, AuthorizeKind);
+AfbDataConverter!(boot_notification_v201, BootNotificationKind);
+AfbDataConverter!(cancel_reservation_v201, CancelReservationKind);
+AfbDataConverter!(certificate_signed_v201, CertificateSignedKind);
+AfbDataConverter!(change_availability_v201, ChangeAvailabilityKind);
+AfbDataConverter!(clear_cache_v201, ClearCacheKind);
+AfbDataConverter!(clear_charging_profile_v201, ClearChargingProfileKind);
+AfbDataConverter!(clear_display_message_v201, ClearDisplayMessageKind);
+AfbDataConverter!(cleared_charging_limit_v201, ClearedChargingLimitKind);
+AfbDataConverter!(clear_variable_monitoring_v201, ClearVariableMonitoringKind);
+AfbDataConverter!(cost_updated_v201, CostUpdatedKind);
+AfbDataConverter!(customer_information_v201, CustomerInformationKind);
+AfbDataConverter!(data_transfer_v201, DataTransferKind);
+AfbDataConverter!(delete_certificate_v201, DeleteCertificateKind);
+AfbDataConverter!(
+    firmware_status_notification_v201,
+    FirmwareStatusNotificationKind
+);
+AfbDataConverter!(get15118_ev_certificate_v201, Get15118EVCertificateKind);
+AfbDataConverter!(get_base_report_v201, GetBaseReportKind);
+AfbDataConverter!(get_certificate_status_v201, GetCertificateStatusKind);
+AfbDataConverter!(get_charging_profiles_v201, GetChargingProfilesKind);
+AfbDataConverter!(get_composite_schedule_v201, GetCompositeScheduleKind);
+AfbDataConverter!(get_display_messages_v201, GetDisplayMessagesKind);
+AfbDataConverter!(
+    get_installed_certificate_ids_v201,
+    GetInstalledCertificateIdsKind
+);
+AfbDataConverter!(get_local_list_version_v201, GetLocalListVersionKind);
+AfbDataConverter!(get_log_v201, GetLogKind);
+AfbDataConverter!(get_monitoring_report_v201, GetMonitoringReportKind);
+AfbDataConverter!(get_report_v201, GetReportKind);
+AfbDataConverter!(get_transaction_status_v201, GetTransactionStatusKind);
+AfbDataConverter!(get_variables_v201, GetVariablesKind);
+AfbDataConverter!(heartbeat_v201, HeartbeatKind);
+AfbDataConverter!(install_certificate_v201, InstallCertificateKind);
+AfbDataConverter!(log_status_notification_v201, LogStatusNotificationKind);
+AfbDataConverter!(meter_values_v201, MeterValuesKind);
+AfbDataConverter!(notify_charging_limit_v201, NotifyChargingLimitKind);
+AfbDataConverter!(
+    notify_customer_information_v201,
+    NotifyCustomerInformationKind
+);
+AfbDataConverter!(notify_display_messages_v201, NotifyDisplayMessagesKind);
+AfbDataConverter!(notify_ev_charging_needs_v201, NotifyEVChargingNeedsKind);
+AfbDataConverter!(
+    notify_ev_charging_schedule_v201,
+    NotifyEVChargingScheduleKind
+);
+AfbDataConverter!(notify_event_v201, NotifyEventKind);
+AfbDataConverter!(notify_monitoring_report_v201, NotifyMonitoringReportKind);
+AfbDataConverter!(notify_report_v201, NotifyReportKind);
+AfbDataConverter!(publish_firmware_v201, PublishFirmwareKind);
+AfbDataConverter!(
+    publish_firmware_status_notification_v201,
+    PublishFirmwareStatusNotificationKind
+);
+AfbDataConverter!(report_charging_profiles_v201, ReportChargingProfilesKind);
+AfbDataConverter!(request_start_transaction_v201, RequestStartTransactionKind);
+AfbDataConverter!(request_stop_transaction_v201, RequestStopTransactionKind);
+AfbDataConverter!(reservation_status_update_v201, ReservationStatusUpdateKind);
+AfbDataConverter!(reserve_now_v201, ReserveNowKind);
+AfbDataConverter!(remote_reset_v201, ResetKind);
+AfbDataConverter!(
+    security_event_notification_v201,
+    SecurityEventNotificationKind
+);
+AfbDataConverter!(send_local_list_v201, SendLocalListKind);
+AfbDataConverter!(set_charging_profile_v201, SetChargingProfileKind);
+AfbDataConverter!(set_display_message_v201, SetDisplayMessageKind);
+AfbDataConverter!(set_monitoring_base_v201, SetMonitoringBaseKind);
+AfbDataConverter!(set_monitoring_level_v201, SetMonitoringLevelKind);
+AfbDataConverter!(set_network_profile_v201, SetNetworkProfileKind);
+AfbDataConverter!(set_variable_monitoring_v201, SetVariableMonitoringKind);
+AfbDataConverter!(set_variables_v201, SetVariablesKind);
+AfbDataConverter!(sign_certificate_v201, SignCertificateKind);
+AfbDataConverter!(status_notification_v201, StatusNotificationKind);
+AfbDataConverter!(transaction_event_v201, TransactionEventKind);
+AfbDataConverter!(trigger_message_v201, TriggerMessageKind);
+AfbDataConverter!(unlock_connector_v201, UnlockConnectorKind);
+AfbDataConverter!(unpublish_firmware_v201, UnpublishFirmwareKind);
+AfbDataConverter!(update_firmware_v201, UpdateFirmwareKind);
+
+pub fn register_datatype() -> Result<(), AfbError> {
+    // Custom type should be registered at binding startup time
+    authorization_v201::register()?;
+    boot_notification_v201::register()?;
+    cancel_reservation_v201::register()?;
+    certificate_signed_v201::register()?;
+    change_availability_v201::register()?;
+    clear_cache_v201::register()?;
+    clear_charging_profile_v201::register()?;
+    clear_display_message_v201::register()?;
+    cleared_charging_limit_v201::register()?;
+    clear_variable_monitoring_v201::register()?;
+    cost_updated_v201::register()?;
+    customer_information_v201::register()?;
+    data_transfer_v201::register()?;
+    delete_certificate_v201::register()?;
+    firmware_status_notification_v201::register()?;
+    get15118_ev_certificate_v201::register()?;
+    get_base_report_v201::register()?;
+    get_certificate_status_v201::register()?;
+    get_charging_profiles_v201::register()?;
+    get_composite_schedule_v201::register()?;
+    get_display_messages_v201::register()?;
+    get_installed_certificate_ids_v201::register()?;
+    get_local_list_version_v201::register()?;
+    get_log_v201::register()?;
+    get_monitoring_report_v201::register()?;
+    get_report_v201::register()?;
+    get_transaction_status_v201::register()?;
+    get_variables_v201::register()?;
+    heartbeat_v201::register()?;
+    install_certificate_v201::register()?;
+    log_status_notification_v201::register()?;
+    meter_values_v201::register()?;
+    notify_charging_limit_v201::register()?;
+    notify_customer_information_v201::register()?;
+    notify_display_messages_v201::register()?;
+    notify_ev_charging_needs_v201::register()?;
+    notify_ev_charging_schedule_v201::register()?;
+    notify_event_v201::register()?;
+    notify_monitoring_report_v201::register()?;
+    notify_report_v201::register()?;
+    publish_firmware_v201::register()?;
+    publish_firmware_status_notification_v201::register()?;
+    report_charging_profiles_v201::register()?;
+    request_start_transaction_v201::register()?;
+    request_stop_transaction_v201::register()?;
+    reservation_status_update_v201::register()?;
+    reserve_now_v201::register()?;
+    remote_reset_v201::register()?;
+    security_event_notification_v201::register()?;
+    send_local_list_v201::register()?;
+    set_charging_profile_v201::register()?;
+    set_display_message_v201::register()?;
+    set_monitoring_base_v201::register()?;
+    set_monitoring_level_v201::register()?;
+    set_network_profile_v201::register()?;
+    set_variable_monitoring_v201::register()?;
+    set_variables_v201::register()?;
+    sign_certificate_v201::register()?;
+    status_notification_v201::register()?;
+    transaction_event_v201::register()?;
+    trigger_message_v201::register()?;
+    unlock_connector_v201::register()?;
+    unpublish_firmware_v201::register()?;
+    update_firmware_v201::register()?;
+    Ok(())
+}
+
+// unit variants serialize as action name string e.g. "BootNotification"
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
-#[serde(untagged)]
 pub enum OcppActionEnum {
     Authorize,
     BootNotification,
@@ -690,9 +1145,9 @@ impl FromStr for OcppActionEnum {
             "Get15118EVCertificate" => Ok(OcppActionEnum::Get15118EVCertificate),
             "GetBaseReport" => Ok(OcppActionEnum::GetBaseReport),
             "GetCertificateStatus" => Ok(OcppActionEnum::GetCertificateStatus),
-            "GetChargingProfile" => Ok(OcppActionEnum::GetChargingProfile),
+            "GetChargingProfile" | "GetChargingProfiles" => Ok(OcppActionEnum::GetChargingProfile),
             "GetCompositeSchedule" => Ok(OcppActionEnum::GetCompositeSchedule),
-            "GetDisplayMessage" => Ok(OcppActionEnum::GetDisplayMessage),
+            "GetDisplayMessage" | "GetDisplayMessages" => Ok(OcppActionEnum::GetDisplayMessage),
             "GetInstalledCertificateIds" => Ok(OcppActionEnum::GetInstalledCertificateIds),
             "GetLocalListVersion" => Ok(OcppActionEnum::GetLocalListVersion),
             "GetLog" => Ok(OcppActionEnum::GetLog),
@@ -811,3 +1266,416 @@ pub enum OcppPayload {
     UnpublishFirmware(UnpublishFirmwareKind),
     UpdateFirmware(UpdateFirmwareKind),
 }
+
+impl OcppPayload {
+    // action name is carried by the OCPP-J frame, payload direction by the frame type
+    pub fn from_request(
+        action: &OcppActionEnum,
+        payload: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        let response = match action {
+            OcppActionEnum::Authorize => {
+                OcppPayload::Authorize(AuthorizeKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::BootNotification => OcppPayload::BootNotification(
+                BootNotificationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::CancelReservation => OcppPayload::CancelReservation(
+                CancelReservationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::CertificateSigned => OcppPayload::CertificateSigned(
+                CertificateSignedKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ChangeAvailability => OcppPayload::ChangeAvailability(
+                ChangeAvailabilityKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearCache => {
+                OcppPayload::ClearCache(ClearCacheKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::ClearChargingProfile => OcppPayload::ClearChargingProfile(
+                ClearChargingProfileKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearDisplayMessage => OcppPayload::ClearDisplayMessage(
+                ClearDisplayMessageKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearedChargingLimit => OcppPayload::ClearedChargingLimit(
+                ClearedChargingLimitKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearVariableMonitoring => OcppPayload::ClearVariableMonitoring(
+                ClearVariableMonitoringKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::CostUpdated => {
+                OcppPayload::CostUpdated(CostUpdatedKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::CustomerInformation => OcppPayload::CustomerInformation(
+                CustomerInformationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::DataTransfer => OcppPayload::DataTransfer(DataTransferKind::Request(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::DeleteCertificate => OcppPayload::DeleteCertificate(
+                DeleteCertificateKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::FirmwareStatusNotification => OcppPayload::FirmwareStatusNotification(
+                FirmwareStatusNotificationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::Get15118EVCertificate => OcppPayload::Get15118EVCertificate(
+                Get15118EVCertificateKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetBaseReport => OcppPayload::GetBaseReport(
+                GetBaseReportKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetCertificateStatus => OcppPayload::GetCertificateStatus(
+                GetCertificateStatusKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetChargingProfile => OcppPayload::GetChargingProfile(
+                GetChargingProfilesKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetCompositeSchedule => OcppPayload::GetCompositeSchedule(
+                GetCompositeScheduleKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetDisplayMessage => OcppPayload::GetDisplayMessage(
+                GetDisplayMessagesKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetInstalledCertificateIds => OcppPayload::GetInstalledCertificateIds(
+                GetInstalledCertificateIdsKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetLocalListVersion => OcppPayload::GetLocalListVersion(
+                GetLocalListVersionKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetLog => {
+                OcppPayload::GetLog(GetLogKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::GetMonitoringReport => OcppPayload::GetMonitoringReport(
+                GetMonitoringReportKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetReport => {
+                OcppPayload::GetReport(GetReportKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::GetTransactionStatus => OcppPayload::GetTransactionStatus(
+                GetTransactionStatusKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetVariables => OcppPayload::GetVariables(GetVariablesKind::Request(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::Heartbeat => {
+                OcppPayload::Heartbeat(HeartbeatKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::InstallCertificate => OcppPayload::InstallCertificate(
+                InstallCertificateKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::LogStatusNotification => OcppPayload::LogStatusNotification(
+                LogStatusNotificationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::MeterValues => {
+                OcppPayload::MeterValues(MeterValuesKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::NotifyChargingLimit => OcppPayload::NotifyChargingLimit(
+                NotifyChargingLimitKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyCustomerInformation => OcppPayload::NotifyCustomerInformation(
+                NotifyCustomerInformationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyDisplayMessages => OcppPayload::NotifyDisplayMessages(
+                NotifyDisplayMessagesKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyEVChargingNeeds => OcppPayload::NotifyEVChargingNeeds(
+                NotifyEVChargingNeedsKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyEVChargingSchedule => OcppPayload::NotifyEVChargingSchedule(
+                NotifyEVChargingScheduleKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyEvent => {
+                OcppPayload::NotifyEvent(NotifyEventKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::NotifyMonitoringReport => OcppPayload::NotifyMonitoringReport(
+                NotifyMonitoringReportKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyReport => OcppPayload::NotifyReport(NotifyReportKind::Request(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::PublishFirmware => OcppPayload::PublishFirmware(
+                PublishFirmwareKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::PublishFirmwareStatusNotification => {
+                OcppPayload::PublishFirmwareStatusNotification(
+                    PublishFirmwareStatusNotificationKind::Request(serde_json::from_value(
+                        payload,
+                    )?),
+                )
+            }
+            OcppActionEnum::ReportChargingProfiles => OcppPayload::ReportChargingProfiles(
+                ReportChargingProfilesKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::RequestStartTransaction => OcppPayload::RequestStartTransaction(
+                RequestStartTransactionKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::RequestStopTransaction => OcppPayload::RequestStopTransaction(
+                RequestStopTransactionKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ReservationStatusUpdate => OcppPayload::ReservationStatusUpdate(
+                ReservationStatusUpdateKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ReserveNow => {
+                OcppPayload::ReserveNow(ReserveNowKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::Reset => {
+                OcppPayload::Reset(ResetKind::Request(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::SecurityEventNotification => OcppPayload::SecurityEventNotification(
+                SecurityEventNotificationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SendLocalList => OcppPayload::SendLocalList(
+                SendLocalListKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetChargingProfile => OcppPayload::SetChargingProfile(
+                SetChargingProfileKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetDisplayMessage => OcppPayload::SetDisplayMessage(
+                SetDisplayMessageKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetMonitoringBase => OcppPayload::SetMonitoringBase(
+                SetMonitoringBaseKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetMonitoringLevel => OcppPayload::SetMonitoringLevel(
+                SetMonitoringLevelKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetNetworkProfile => OcppPayload::SetNetworkProfile(
+                SetNetworkProfileKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetVariableMonitoring => OcppPayload::SetVariableMonitoring(
+                SetVariableMonitoringKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetVariables => OcppPayload::SetVariables(SetVariablesKind::Request(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::SignCertificate => OcppPayload::SignCertificate(
+                SignCertificateKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::StatusNotification => OcppPayload::StatusNotification(
+                StatusNotificationKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::TransactionEvent => OcppPayload::TransactionEvent(
+                TransactionEventKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::TriggerMessage => OcppPayload::TriggerMessage(
+                TriggerMessageKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::UnlockConnector => OcppPayload::UnlockConnector(
+                UnlockConnectorKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::UnpublishFirmware => OcppPayload::UnpublishFirmware(
+                UnpublishFirmwareKind::Request(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::UpdateFirmware => OcppPayload::UpdateFirmware(
+                UpdateFirmwareKind::Request(serde_json::from_value(payload)?),
+            ),
+        };
+        Ok(response)
+    }
+
+    pub fn from_response(
+        action: &OcppActionEnum,
+        payload: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        let response = match action {
+            OcppActionEnum::Authorize => {
+                OcppPayload::Authorize(AuthorizeKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::BootNotification => OcppPayload::BootNotification(
+                BootNotificationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::CancelReservation => OcppPayload::CancelReservation(
+                CancelReservationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::CertificateSigned => OcppPayload::CertificateSigned(
+                CertificateSignedKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ChangeAvailability => OcppPayload::ChangeAvailability(
+                ChangeAvailabilityKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearCache => {
+                OcppPayload::ClearCache(ClearCacheKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::ClearChargingProfile => OcppPayload::ClearChargingProfile(
+                ClearChargingProfileKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearDisplayMessage => OcppPayload::ClearDisplayMessage(
+                ClearDisplayMessageKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearedChargingLimit => OcppPayload::ClearedChargingLimit(
+                ClearedChargingLimitKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ClearVariableMonitoring => OcppPayload::ClearVariableMonitoring(
+                ClearVariableMonitoringKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::CostUpdated => OcppPayload::CostUpdated(CostUpdatedKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::CustomerInformation => OcppPayload::CustomerInformation(
+                CustomerInformationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::DataTransfer => OcppPayload::DataTransfer(DataTransferKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::DeleteCertificate => OcppPayload::DeleteCertificate(
+                DeleteCertificateKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::FirmwareStatusNotification => OcppPayload::FirmwareStatusNotification(
+                FirmwareStatusNotificationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::Get15118EVCertificate => OcppPayload::Get15118EVCertificate(
+                Get15118EVCertificateKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetBaseReport => OcppPayload::GetBaseReport(
+                GetBaseReportKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetCertificateStatus => OcppPayload::GetCertificateStatus(
+                GetCertificateStatusKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetChargingProfile => OcppPayload::GetChargingProfile(
+                GetChargingProfilesKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetCompositeSchedule => OcppPayload::GetCompositeSchedule(
+                GetCompositeScheduleKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetDisplayMessage => OcppPayload::GetDisplayMessage(
+                GetDisplayMessagesKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetInstalledCertificateIds => OcppPayload::GetInstalledCertificateIds(
+                GetInstalledCertificateIdsKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetLocalListVersion => OcppPayload::GetLocalListVersion(
+                GetLocalListVersionKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetLog => {
+                OcppPayload::GetLog(GetLogKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::GetMonitoringReport => OcppPayload::GetMonitoringReport(
+                GetMonitoringReportKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetReport => {
+                OcppPayload::GetReport(GetReportKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::GetTransactionStatus => OcppPayload::GetTransactionStatus(
+                GetTransactionStatusKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::GetVariables => OcppPayload::GetVariables(GetVariablesKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::Heartbeat => {
+                OcppPayload::Heartbeat(HeartbeatKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::InstallCertificate => OcppPayload::InstallCertificate(
+                InstallCertificateKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::LogStatusNotification => OcppPayload::LogStatusNotification(
+                LogStatusNotificationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::MeterValues => OcppPayload::MeterValues(MeterValuesKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::NotifyChargingLimit => OcppPayload::NotifyChargingLimit(
+                NotifyChargingLimitKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyCustomerInformation => OcppPayload::NotifyCustomerInformation(
+                NotifyCustomerInformationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyDisplayMessages => OcppPayload::NotifyDisplayMessages(
+                NotifyDisplayMessagesKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyEVChargingNeeds => OcppPayload::NotifyEVChargingNeeds(
+                NotifyEVChargingNeedsKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyEVChargingSchedule => OcppPayload::NotifyEVChargingSchedule(
+                NotifyEVChargingScheduleKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyEvent => OcppPayload::NotifyEvent(NotifyEventKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::NotifyMonitoringReport => OcppPayload::NotifyMonitoringReport(
+                NotifyMonitoringReportKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::NotifyReport => OcppPayload::NotifyReport(NotifyReportKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::PublishFirmware => OcppPayload::PublishFirmware(
+                PublishFirmwareKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::PublishFirmwareStatusNotification => {
+                OcppPayload::PublishFirmwareStatusNotification(
+                    PublishFirmwareStatusNotificationKind::Response(serde_json::from_value(
+                        payload,
+                    )?),
+                )
+            }
+            OcppActionEnum::ReportChargingProfiles => OcppPayload::ReportChargingProfiles(
+                ReportChargingProfilesKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::RequestStartTransaction => OcppPayload::RequestStartTransaction(
+                RequestStartTransactionKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::RequestStopTransaction => OcppPayload::RequestStopTransaction(
+                RequestStopTransactionKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ReservationStatusUpdate => OcppPayload::ReservationStatusUpdate(
+                ReservationStatusUpdateKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::ReserveNow => {
+                OcppPayload::ReserveNow(ReserveNowKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::Reset => {
+                OcppPayload::Reset(ResetKind::Response(serde_json::from_value(payload)?))
+            }
+            OcppActionEnum::SecurityEventNotification => OcppPayload::SecurityEventNotification(
+                SecurityEventNotificationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SendLocalList => OcppPayload::SendLocalList(
+                SendLocalListKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetChargingProfile => OcppPayload::SetChargingProfile(
+                SetChargingProfileKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetDisplayMessage => OcppPayload::SetDisplayMessage(
+                SetDisplayMessageKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetMonitoringBase => OcppPayload::SetMonitoringBase(
+                SetMonitoringBaseKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetMonitoringLevel => OcppPayload::SetMonitoringLevel(
+                SetMonitoringLevelKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetNetworkProfile => OcppPayload::SetNetworkProfile(
+                SetNetworkProfileKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetVariableMonitoring => OcppPayload::SetVariableMonitoring(
+                SetVariableMonitoringKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::SetVariables => OcppPayload::SetVariables(SetVariablesKind::Response(
+                serde_json::from_value(payload)?,
+            )),
+            OcppActionEnum::SignCertificate => OcppPayload::SignCertificate(
+                SignCertificateKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::StatusNotification => OcppPayload::StatusNotification(
+                StatusNotificationKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::TransactionEvent => OcppPayload::TransactionEvent(
+                TransactionEventKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::TriggerMessage => OcppPayload::TriggerMessage(
+                TriggerMessageKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::UnlockConnector => OcppPayload::UnlockConnector(
+                UnlockConnectorKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::UnpublishFirmware => OcppPayload::UnpublishFirmware(
+                UnpublishFirmwareKind::Response(serde_json::from_value(payload)?),
+            ),
+            OcppActionEnum::UpdateFirmware => OcppPayload::UpdateFirmware(
+                UpdateFirmwareKind::Response(serde_json::from_value(payload)?),
+            ),
+        };
+        Ok(response)
+    }
+}
@@ -24,9 +24,23 @@
     html_favicon_url = "https://iot.bzh/images/defaults/favicon.ico"
 )]
 
-#[cfg(not(afbv4))]
+#[cfg(feature = "afbv4")]
 extern crate afbv4;
 
+// bind OCPP request/response payloads to their action name and direction
+macro_rules! ocpp_action {
+    ($action:literal, $request:ty, $response:ty) => {
+        impl crate::rpc::OcppAction for $request {
+            const ACTION: &'static str = $action;
+            const DIRECTION: crate::rpc::OcppDirection = crate::rpc::OcppDirection::Request;
+        }
+        impl crate::rpc::OcppAction for $response {
+            const ACTION: &'static str = $action;
+            const DIRECTION: crate::rpc::OcppDirection = crate::rpc::OcppDirection::Response;
+        }
+    };
+}
+
 #[path = "enums-v106.rs"]
 pub mod v106;
 
@@ -39,6 +53,22 @@ pub mod manager;
 #[path = "utils.rs"]
 pub mod utils;
 
+#[path = "ocpp-rpc.rs"]
+pub mod rpc;
+
+#[path = "charge-point.rs"]
+pub mod chargepoint;
+
+#[path = "config-store.rs"]
+pub mod config;
+
+#[path = "auth-store.rs"]
+pub mod auth;
+
+#[cfg(feature = "websocket")]
+#[path = "ws-client.rs"]
+pub mod wsclient;
+
 
 // export to external crate restricted to session APIs
 pub mod prelude {
@@ -46,4 +76,10 @@ pub mod prelude {
     pub use crate::v201;
     pub use crate::manager::*;
     pub use crate::utils::*;
+    pub use crate::rpc::*;
+    pub use crate::chargepoint::*;
+    pub use crate::config::*;
+    pub use crate::auth::*;
+    #[cfg(feature = "websocket")]
+    pub use crate::wsclient::*;
 }
\ No newline at end of file
@@ -12,177 +12,871 @@
 
 //use crate::prelude::*;
 use crate::prelude::*;
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
 use std::sync::{Mutex, MutexGuard};
 use typesv4::prelude::*;
 
-pub struct ManagerHandle {
+// store private entry holding inoperative connectors
+const PRIVATE_INOPERATIVE: &str = "inoperative";
+
+// retry delay when csms does not provide a BootNotification interval
+pub const BOOT_RETRY_DEFAULT: u32 = 60;
+
+// BootNotification registration, csms only accepts other messages once Accepted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OcppBootStatus {
+    Idle,
+    Sent,
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+struct OcppBootState {
+    status: OcppBootStatus,
+    retry: u32,
+}
+
+// ChangeAvailability, scheduled change waits for running transaction to end
+struct OcppAvailability {
+    operative: bool,
+    scheduled: Option<bool>,
+}
+
+// RemoteStartTransaction waiting for EV plug, nonce discards outdated timeout
+struct OcppRemoteStart {
+    id_tag: String,
+    limit: Option<PowerLimit>,
+    nonce: u32,
+    // authorized and notified to chmgr
+    ready: bool,
+    // StartTransaction sent by the binding
+    started: bool,
+}
+
+// StartTransaction/StopTransaction built while csms was unreachable, query keeps its original timestamp
+#[derive(Debug, Clone)]
+pub struct OcppOfflineTx {
+    pub cid: u32,
+    // provisional id until csms returns its own one (1.6), charge point id (2.0.1)
+    pub tid: i32,
+    pub id_tag: Option<String>,
+    pub start: bool,
+    pub query: OcppQuery,
+}
+
+// queued messages are replayed one at a time and in order once csms answers again
+struct OcppOfflineQueue {
+    pending: VecDeque<OcppOfflineTx>,
+    replaying: bool,
+    // provisional id -> csms transaction id
+    tids: HashMap<i32, i32>,
+    provisional: i32,
+    // failed replays of the oldest message, seconds before it is sent again
+    attempts: u32,
+    retry: u32,
+    // transactions whose StartTransaction was dropped while they still run
+    dropped: HashSet<i32>,
+}
+
+// per connector state, each connector pushes its own OcppMsg event
+struct OcppConnector {
+    cid: u32,
     event: &'static AfbEvent,
     data_set: Mutex<OcppState>,
+    availability: Mutex<OcppAvailability>,
+    // last energy register pushed by engy, used when transaction is closed by charge point (reset)
+    meter: AtomicI32,
+    remote: Mutex<Option<OcppRemoteStart>>,
+    // transaction id requested to stop by csms, StopTransaction reason is Remote
+    remote_stop: AtomicI32,
+    // idTag which started running transaction
+    id_tag: Mutex<Option<String>>,
+}
+
+impl OcppConnector {
+    fn new(cid: u32, event: &'static AfbEvent) -> Self {
+        let mut state = OcppState::default();
+        state.connector_id = cid;
+        OcppConnector {
+            cid,
+            event,
+            data_set: Mutex::new(state),
+            availability: Mutex::new(OcppAvailability {
+                operative: true,
+                scheduled: None,
+            }),
+            meter: AtomicI32::new(0),
+            remote: Mutex::new(None),
+            remote_stop: AtomicI32::new(0),
+            id_tag: Mutex::new(None),
+        }
+    }
+
+    fn get_tid(&self) -> i32 {
+        self.data_set.lock().unwrap().tid
+    }
+
+    // return new status when availability changed, station (connector 0) does not push events
+    fn set_operative(&self, operative: bool) -> Option<OcppChargerStatus> {
+        let mut availability = self.availability.lock().unwrap();
+        availability.scheduled = None;
+        if availability.operative == operative {
+            return None;
+        }
+        availability.operative = operative;
+
+        let mut data_set = self.data_set.lock().unwrap();
+        data_set.status = if operative {
+            OcppChargerStatus::Available
+        } else {
+            OcppChargerStatus::Unavailable
+        };
+        if self.cid != 0 {
+            self.event.push(OcppMsg::Availability(operative));
+        }
+        Some(data_set.status.clone())
+    }
+}
+
+pub struct ManagerHandle {
+    // connector 0, whole charge point status
+    station: OcppConnector,
+    connectors: Vec<OcppConnector>,
+    boot: Mutex<OcppBootState>,
     cid: u32,
+    // some csms (e.g. biapower) refuse connector 0
+    connector_zero: bool,
+    // persist availability when defined
+    store: Option<&'static OcppConfigStore>,
+    remote_nonce: AtomicU32,
+    // 2.0.1 OnIdle reset waiting for running transactions to end
+    reset_pending: Mutex<Option<OcppStopReason>>,
+    offline: Mutex<OcppOfflineQueue>,
 }
 
 impl ManagerHandle {
+    // cid is the default connector used when frontend verbs do not provide one
     pub fn new(
         cid: u32,
         event: &'static AfbEvent,
     ) -> &'static mut Self {
-        let handle = ManagerHandle {
-            data_set: Mutex::new(OcppState::default()),
-            event,
+        let station = OcppConnector::new(0, event);
+        station.data_set.lock().unwrap().status = OcppChargerStatus::Available;
+        let mut handle = ManagerHandle {
+            station,
+            connectors: Vec::new(),
+            boot: Mutex::new(OcppBootState {
+                status: OcppBootStatus::Idle,
+                retry: 0,
+            }),
             cid,
+            connector_zero: true,
+            store: None,
+            remote_nonce: AtomicU32::new(0),
+            reset_pending: Mutex::new(None),
+            offline: Mutex::new(OcppOfflineQueue {
+                pending: VecDeque::new(),
+                replaying: false,
+                tids: HashMap::new(),
+                provisional: 0,
+                attempts: 0,
+                retry: 0,
+                dropped: HashSet::new(),
+            }),
         };
+        handle.push_connector(cid, event);
 
         // return a static handle to prevent Rust from complaining when moving/sharing it
         Box::leak(Box::new(handle))
     }
 
+    fn push_connector(&mut self, cid: u32, event: &'static AfbEvent) {
+        self.connectors.push(OcppConnector::new(cid, event));
+    }
+
+    // should be called once connectors are added, restore availability persisted by previous run
+    pub fn set_store(&mut self, store: &'static OcppConfigStore) {
+        if let Some(Value::Array(list)) = store.get_private(PRIVATE_INOPERATIVE) {
+            for cid in list.iter().filter_map(|value| value.as_u64()) {
+                if let Ok(connector) = self.get_connector(cid as u32) {
+                    connector.set_operative(false);
+                }
+            }
+        }
+        self.store = Some(store);
+    }
+
+    fn save_availability(&self) -> Result<(), AfbError> {
+        let store = match self.store {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        let inoperative = std::iter::once(&self.station)
+            .chain(self.connectors.iter())
+            .filter(|connector| {
+                let availability = connector.availability.lock().unwrap();
+                !availability.scheduled.unwrap_or(availability.operative)
+            })
+            .map(|connector| connector.cid)
+            .collect::<Vec<u32>>();
+        store.set_private(PRIVATE_INOPERATIVE, json!(inoperative))
+    }
+
+    // should be called before handle is shared, connector 0 is reserved for the whole station
+    pub fn add_connector(&mut self, cid: u32, event: &'static AfbEvent) -> Result<(), AfbError> {
+        if cid == 0 || self.get_connectors().contains(&cid) {
+            return afb_error!("ocpp-connector-add", "invalid or duplicated connector:{}", cid);
+        }
+        self.push_connector(cid, event);
+        Ok(())
+    }
+
+    // should be called before handle is shared
+    pub fn set_connector_zero(&mut self, enable: bool) {
+        self.connector_zero = enable;
+    }
+
+    pub fn has_connector_zero(&self) -> bool {
+        self.connector_zero
+    }
+
+    fn get_connector(&self, cid: u32) -> Result<&OcppConnector, AfbError> {
+        if cid == 0 {
+            return Ok(&self.station);
+        }
+        match self
+            .connectors
+            .iter()
+            .find(|connector| connector.cid == cid)
+        {
+            Some(connector) => Ok(connector),
+            None => afb_error!("ocpp-unknown-connector", "connector:{} does not exist", cid),
+        }
+    }
+
     #[track_caller]
-    pub fn get_state(&self) -> Result<MutexGuard<'_, OcppState>, AfbError> {
-        let guard = self.data_set.lock().unwrap();
+    pub fn get_state(&self, cid: u32) -> Result<MutexGuard<'_, OcppState>, AfbError> {
+        let guard = self.get_connector(cid)?.data_set.lock().unwrap();
         Ok(guard)
     }
 
-    pub fn subscribe(&self, request: &AfbRequest, subscription: bool) -> Result<(), AfbError> {
+    // physical connectors, connector 0 excluded
+    pub fn get_connectors(&self) -> Vec<u32> {
+        self.connectors
+            .iter()
+            .map(|connector| connector.cid)
+            .collect()
+    }
+
+    // connectors reported through StatusNotification, station first when csms supports connector 0
+    pub fn get_status_connectors(&self) -> Vec<u32> {
+        let mut connectors = Vec::new();
+        if self.connector_zero {
+            connectors.push(0);
+        }
+        connectors.extend(self.get_connectors());
+        connectors
+    }
+
+    // csms requests addressed to connector 0 fan out to every connector
+    pub fn get_targets(&self, cid: u32) -> Result<Vec<u32>, AfbError> {
+        if cid == 0 {
+            if !self.connector_zero {
+                return afb_error!("ocpp-connector-zero", "connector 0 disabled for this csms");
+            }
+            return Ok(self.get_connectors());
+        }
+        self.get_connector(cid)?;
+        Ok(vec![cid])
+    }
+
+    // connector owning a running transaction
+    pub fn find_tid(&self, tid: i32) -> Option<u32> {
+        self.connectors.iter().find_map(|connector| {
+            if tid != 0 && connector.data_set.lock().unwrap().tid == tid {
+                Some(connector.cid)
+            } else {
+                None
+            }
+        })
+    }
+
+    #[cfg(feature = "afbv4")]
+    pub fn subscribe(&self, request: &AfbRequest, cid: u32, subscription: bool) -> Result<(), AfbError> {
+        let event = self.get_connector(cid)?.event;
         if subscription {
-            self.event.subscribe(request)?;
+            event.subscribe(request)?;
         } else {
-            self.event.unsubscribe(request)?;
+            event.unsubscribe(request)?;
         }
         Ok(())
     }
 
-    pub fn check_active_session(&self, status: bool) -> Result<(), AfbError> {
-        let data_set = self.get_state()?;
+    pub fn check_active_session(&self, cid: u32, status: bool) -> Result<(), AfbError> {
+        // StartTransaction already sent by the binding for a remote start
+        let remote = self.get_connector(cid)?.remote.lock().unwrap().as_ref().map_or(false, |remote| remote.started);
+        if !status && remote {
+            return afb_error!("ocpp-running-session", "Remote start transaction pending connector:{}", cid);
+        }
+        let data_set = self.get_state(cid)?;
         if status && data_set.tid == 0 {
-            return afb_error!("ocpp-active-session", "No active session tid");
+            return afb_error!("ocpp-active-session", "No active session tid connector:{}", cid);
         }
         if !status && data_set.tid != 0 {
             return afb_error!(
                 "ocpp-running-session",
-                "Running session tid:{} already running",
-                data_set.tid
+                "Running session tid:{} already running connector:{}",
+                data_set.tid,
+                cid
             );
         }
         Ok(())
     }
 
+    pub fn get_boot(&self) -> OcppBootStatus {
+        self.boot.lock().unwrap().status
+    }
+
+    // non boot messages are rejected until csms accepted BootNotification
+    pub fn check_boot(&self) -> Result<(), AfbError> {
+        let status = self.get_boot();
+        if status != OcppBootStatus::Accepted {
+            return afb_error!("ocpp-boot-pending", "BootNotification not accepted status:{:?}", status);
+        }
+        Ok(())
+    }
+
+    pub fn boot_sent(&self) {
+        let mut boot = self.boot.lock().unwrap();
+        boot.status = OcppBootStatus::Sent;
+    }
+
+    // register csms response, interval (seconds) is the retry delay when not accepted
+    pub fn boot_update(&self, status: OcppBootStatus, interval: u32) {
+        let mut boot = self.boot.lock().unwrap();
+        boot.status = status;
+        boot.retry = if interval == 0 { BOOT_RETRY_DEFAULT } else { interval };
+    }
+
+    // count down one second, return true when BootNotification should be sent again
+    pub fn boot_tick(&self) -> bool {
+        let mut boot = self.boot.lock().unwrap();
+        match boot.status {
+            OcppBootStatus::Accepted | OcppBootStatus::Sent => false,
+            _ => {
+                boot.retry = boot.retry.saturating_sub(1);
+                boot.retry == 0
+            }
+        }
+    }
+
     pub fn get_cid(&self) -> u32 {
         self.cid
     }
 
-    pub fn set_status(&self, status: &OcppChargerStatus) -> Result<(), AfbError> {
-        let mut data_set = self.get_state()?;
-        data_set.status= status.clone();
+    // inoperative connectors remain Unavailable unless faulted, return effective status
+    pub fn set_status(&self, cid: u32, status: &OcppChargerStatus) -> Result<OcppChargerStatus, AfbError> {
+        let connector = self.get_connector(cid)?;
+        let operative = connector.availability.lock().unwrap().operative;
+        let status = match status {
+            OcppChargerStatus::Error(_) => status.clone(),
+            _ if !operative => OcppChargerStatus::Unavailable,
+            _ => status.clone(),
+        };
+        connector.data_set.lock().unwrap().status = status.clone();
+        Ok(status)
+    }
+
+    pub fn check_operative(&self, cid: u32) -> Result<(), AfbError> {
+        if !self.get_connector(cid)?.availability.lock().unwrap().operative {
+            return afb_error!("ocpp-connector-inoperative", "connector:{} is unavailable", cid);
+        }
         Ok(())
     }
 
-    pub fn get_status(&self) -> Result<OcppChargerStatus, AfbError> {
-        let data_set = self.get_state()?;
+    // connector 0 applies to station and every connector, a running transaction delays change (Scheduled)
+    // return csms status and connectors whose status changed
+    pub fn change_availability(
+        &self,
+        cid: u32,
+        operative: bool,
+    ) -> Result<(v106::AvailabilityStatus, Vec<(u32, OcppChargerStatus)>), AfbError> {
+        let mut targets = match self.get_targets(cid) {
+            Ok(value) => value,
+            Err(_) => return Ok((v106::AvailabilityStatus::Rejected, Vec::new())),
+        };
+        if cid == 0 {
+            targets.insert(0, 0);
+        }
+
+        let mut response = v106::AvailabilityStatus::Accepted;
+        let mut changed = Vec::new();
+        for target in targets {
+            let connector = self.get_connector(target)?;
+            let busy = match target {
+                0 => self.connectors.iter().any(|connector| connector.get_tid() != 0),
+                _ => connector.get_tid() != 0,
+            };
+            if busy {
+                connector.availability.lock().unwrap().scheduled = Some(operative);
+                response = v106::AvailabilityStatus::Scheduled;
+            } else if let Some(status) = connector.set_operative(operative) {
+                changed.push((target, status));
+            }
+        }
+        self.save_availability()?;
+        Ok((response, changed))
+    }
+
+    // apply availability scheduled during a transaction, station waits for every transaction
+    pub fn apply_scheduled(&self, cid: u32) -> Result<Vec<(u32, OcppChargerStatus)>, AfbError> {
+        let mut changed = Vec::new();
+        let connector = self.get_connector(cid)?;
+        let scheduled = connector.availability.lock().unwrap().scheduled;
+        if let Some(operative) = scheduled {
+            if let Some(status) = connector.set_operative(operative) {
+                changed.push((cid, status));
+            }
+        }
+
+        let scheduled = self.station.availability.lock().unwrap().scheduled;
+        if let Some(operative) = scheduled {
+            if self.connectors.iter().all(|connector| connector.get_tid() == 0) {
+                if let Some(status) = self.station.set_operative(operative) {
+                    changed.push((0, status));
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    pub fn get_status(&self, cid: u32) -> Result<OcppChargerStatus, AfbError> {
+        let data_set = self.get_state(cid)?;
         Ok(data_set.status.clone())
     }
 
-    pub fn get_tid(&self) -> Result<i32, AfbError> {
-        let data_set = self.get_state()?;
+    pub fn get_tid(&self, cid: u32) -> Result<i32, AfbError> {
+        let data_set = self.get_state(cid)?;
         Ok(data_set.tid)
     }
 
-    pub fn authorized(&self, authorized: bool) -> Result<(), AfbError> {
-        let mut data_set = self.get_state()?;
-        self.event.push(OcppMsg::Authorized(authorized));
+    pub fn authorized(&self, cid: u32, authorized: bool) -> Result<(), AfbError> {
+        let connector = self.get_connector(cid)?;
+        let mut data_set = connector.data_set.lock().unwrap();
+        connector.event.push(OcppMsg::Authorized(authorized));
         data_set.authorized = authorized;
         Ok(())
     }
 
-    pub fn login(&self, tid: i32) -> Result<(), AfbError> {
-        let mut data_set = self.get_state()?;
+    pub fn set_meter(&self, cid: u32, meter: i32) -> Result<(), AfbError> {
+        self.get_connector(cid)?.meter.store(meter, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn get_meter(&self, cid: u32) -> Result<i32, AfbError> {
+        Ok(self.get_connector(cid)?.meter.load(Ordering::Relaxed))
+    }
+
+    // connectors running a transaction
+    pub fn get_transactions(&self) -> Vec<u32> {
+        self.connectors
+            .iter()
+            .filter(|connector| connector.get_tid() != 0)
+            .map(|connector| connector.cid)
+            .collect()
+    }
+
+    // a pending remote start is consumed, its charging profile applies to the new transaction
+    pub fn login(&self, cid: u32, tid: i32, id_tag: &str) -> Result<(), AfbError> {
+        let connector = self.get_connector(cid)?;
+        let remote = connector.remote.lock().unwrap().take();
+        let mut data_set = connector.data_set.lock().unwrap();
 
         data_set.tid = tid;
+        *connector.id_tag.lock().unwrap() = Some(id_tag.to_string());
+        connector.meter.store(0, Ordering::Relaxed);
+        if let Some(remote) = remote {
+            // chmgr did not start this transaction
+            if remote.started {
+                connector.event.push(OcppMsg::Transaction(true, tid as u32));
+            }
+            if let Some(mut limit) = remote.limit {
+                limit.tid = tid;
+                connector.event.push(OcppMsg::PowerLimit(limit));
+            }
+        }
         Ok(())
     }
 
-    pub fn logout(&self) -> Result<(), AfbError> {
-        let mut data_set = self.get_state()?;
+    fn remote_free(connector: &OcppConnector, id_tag: &str) -> bool {
+        if !connector.availability.lock().unwrap().operative || connector.remote.lock().unwrap().is_some() {
+            return false;
+        }
+        let data_set = connector.data_set.lock().unwrap();
+        let reserved = match &data_set.reservation {
+            Some(resa) => resa.tagid != id_tag,
+            None => false,
+        };
+        let status = match data_set.status {
+            OcppChargerStatus::Unavailable | OcppChargerStatus::Error(_) => false,
+            _ => true,
+        };
+        data_set.tid == 0 && !reserved && status
+    }
+
+    // RemoteStartTransaction, no connector selects the first free one
+    // return selected connector and nonce, None when csms request should be rejected
+    pub fn remote_start(
+        &self,
+        cid: Option<u32>,
+        id_tag: &str,
+        limit: Option<PowerLimit>,
+    ) -> Option<(u32, u32)> {
+        let connector = match cid {
+            Some(0) => return None,
+            Some(cid) => match self.get_connector(cid) {
+                Ok(connector) if Self::remote_free(connector, id_tag) => connector,
+                _ => return None,
+            },
+            None => self
+                .connectors
+                .iter()
+                .find(|connector| Self::remote_free(connector, id_tag))?,
+        };
+
+        let nonce = self.remote_nonce.fetch_add(1, Ordering::Relaxed) + 1;
+        *connector.remote.lock().unwrap() = Some(OcppRemoteStart {
+            id_tag: id_tag.to_string(),
+            limit,
+            nonce,
+            ready: false,
+            started: false,
+        });
+        Some((connector.cid, nonce))
+    }
+
+    // remote start authorized, notify chmgr and return true when EV is already plugged
+    pub fn remote_start_ready(&self, cid: u32, nonce: u32) -> Result<bool, AfbError> {
+        let connector = self.get_connector(cid)?;
+        let mut remote = connector.remote.lock().unwrap();
+        let remote = match remote.as_mut() {
+            Some(value) if value.nonce == nonce => value,
+            _ => return Ok(false),
+        };
+        remote.ready = true;
+
+        let mut data_set = connector.data_set.lock().unwrap();
+        data_set.authorized = true;
+        connector.event.push(OcppMsg::RemoteStart(remote.id_tag.clone()));
+        Ok(matches!(data_set.status, OcppChargerStatus::Preparing))
+    }
+
+    // EV plugged, return id_tag and nonce once when the binding should send StartTransaction
+    pub fn remote_start_plugged(&self, cid: u32) -> Result<Option<(String, u32)>, AfbError> {
+        let connector = self.get_connector(cid)?;
+        let mut remote = connector.remote.lock().unwrap();
+        match remote.as_mut() {
+            Some(value) if value.ready && !value.started => {
+                value.started = true;
+                Ok(Some((value.id_tag.clone(), value.nonce)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // authorization rejected, StartTransaction failed or timeout (ConnectionTimeOut), false when already consumed
+    // timeout ignores a remote start whose StartTransaction is in flight
+    pub fn remote_start_cancel(&self, cid: u32, nonce: u32, timeout: bool) -> Result<bool, AfbError> {
+        let connector = self.get_connector(cid)?;
+        let mut remote = connector.remote.lock().unwrap();
+        match remote.as_ref() {
+            Some(value) if value.nonce == nonce && !(timeout && value.started) => {}
+            _ => return Ok(false),
+        }
+        *remote = None;
+
+        let mut data_set = connector.data_set.lock().unwrap();
+        if data_set.tid == 0 {
+            data_set.authorized = false;
+            connector.event.push(OcppMsg::Authorized(false));
+        }
+        Ok(true)
+    }
+
+    pub fn logout(&self, cid: u32) -> Result<(), AfbError> {
+        let connector = self.get_connector(cid)?;
+        let mut data_set = connector.data_set.lock().unwrap();
         data_set.tid = 0;
+        connector.remote_stop.store(0, Ordering::Relaxed);
+        *connector.id_tag.lock().unwrap() = None;
         Ok(())
     }
 
+    // None when no transaction runs on connector
+    pub fn get_id_tag(&self, cid: u32) -> Result<Option<String>, AfbError> {
+        Ok(self.get_connector(cid)?.id_tag.lock().unwrap().clone())
+    }
+
+    // reset applies to every connector
     pub fn reset(&self) -> Result<(), AfbError> {
-        self.event.push(OcppMsg::Reset);
-        self.logout()?;
+        for connector in &self.connectors {
+            connector.event.push(OcppMsg::Reset);
+            connector.data_set.lock().unwrap().tid = 0;
+            *connector.id_tag.lock().unwrap() = None;
+        }
+        *self.reset_pending.lock().unwrap() = None;
         Ok(())
     }
 
-    pub fn reserv_now(
-        &self,
-        request: ReservationSession,
-    ) -> Result<v106::ReservationStatus, AfbError> {
-        let mut data_set = self.get_state()?;
+    pub fn reset_schedule(&self, reason: OcppStopReason) {
+        *self.reset_pending.lock().unwrap() = Some(reason);
+    }
 
-        let response = match &data_set.reservation {
+    // scheduled reset is consumed once the last transaction is closed
+    pub fn reset_idle(&self) -> Option<OcppStopReason> {
+        if !self.get_transactions().is_empty() {
+            return None;
+        }
+        self.reset_pending.lock().unwrap().take()
+    }
+
+    #[track_caller]
+    fn get_offline(&self) -> Result<MutexGuard<'_, OcppOfflineQueue>, AfbError> {
+        match self.offline.lock() {
+            Ok(value) => Ok(value),
+            Err(_) => afb_error!("ocpp-offline-queue", "fail to lock offline transactions"),
+        }
+    }
+
+    // negative ids never collide with csms transaction ids
+    pub fn provisional_tid(&self) -> Result<i32, AfbError> {
+        let mut offline = self.get_offline()?;
+        offline.provisional -= 1;
+        Ok(offline.provisional)
+    }
+
+    pub fn offline_push(&self, transaction: OcppOfflineTx) -> Result<(), AfbError> {
+        self.get_offline()?.pending.push_back(transaction);
+        Ok(())
+    }
+
+    // while messages wait for replay, new ones are queued behind them to keep csms ordering
+    pub fn offline_pending(&self) -> Result<bool, AfbError> {
+        Ok(!self.get_offline()?.pending.is_empty())
+    }
+
+    // oldest queued message with its csms transaction id, None when empty, replaying or waiting for retry
+    pub fn offline_next(&self) -> Result<Option<OcppOfflineTx>, AfbError> {
+        let mut offline = self.get_offline()?;
+        if offline.replaying || offline.retry > 0 {
+            return Ok(None);
+        }
+        let mut transaction = match offline.pending.front() {
+            Some(value) => value.clone(),
+            None => return Ok(None),
+        };
+        if let Some(tid) = offline.tids.get(&transaction.tid) {
+            transaction.tid = *tid;
+        }
+        offline.replaying = true;
+        Ok(Some(transaction))
+    }
+
+    // replayed message accepted by csms, StartTransaction response maps provisional id to csms one
+    pub fn offline_done(&self, csms_tid: Option<i32>) -> Result<(), AfbError> {
+        let mut offline = self.get_offline()?;
+        offline.replaying = false;
+        offline.attempts = 0;
+        let transaction = match offline.pending.pop_front() {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        if !transaction.start {
+            offline.tids.remove(&transaction.tid);
+            return Ok(());
+        }
+        if let Some(tid) = csms_tid {
+            offline.tids.insert(transaction.tid, tid);
+            // transaction still running, next StopTransaction is sent with csms id
+            for connector in &self.connectors {
+                let mut data_set = connector.data_set.lock().unwrap();
+                if data_set.tid == transaction.tid {
+                    data_set.tid = tid;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // csms still unreachable, message is kept for next replay
+    pub fn offline_failed(&self) -> Result<(), AfbError> {
+        self.get_offline()?.replaying = false;
+        Ok(())
+    }
+
+    // csms answered with an invalid response, message is retried after TransactionMessageRetryInterval times
+    // previous attempts and dropped after TransactionMessageAttempts
+    pub fn offline_rejected(&self) -> Result<Option<OcppOfflineTx>, AfbError> {
+        let (attempts, interval) = match self.store {
+            Some(store) => (
+                store.get_u32(CFG_TRANSACTION_MESSAGE_ATTEMPTS)?,
+                store.get_u32(CFG_TRANSACTION_MESSAGE_RETRY_INTERVAL)?,
+            ),
+            None => (1, 0),
+        };
+        let mut offline = self.get_offline()?;
+        offline.replaying = false;
+        offline.attempts += 1;
+        if offline.attempts >= attempts {
+            return Ok(Self::offline_pop(&mut offline));
+        }
+        offline.retry = interval.saturating_mul(offline.attempts).max(1);
+        Ok(None)
+    }
+
+    // csms answered with a CALLERROR, message will never be accepted
+    pub fn offline_drop(&self) -> Result<Option<OcppOfflineTx>, AfbError> {
+        let mut offline = self.get_offline()?;
+        offline.replaying = false;
+        Ok(Self::offline_pop(&mut offline))
+    }
+
+    // messages of a dropped StartTransaction are dropped with it, a still running transaction is remembered
+    fn offline_pop(offline: &mut OcppOfflineQueue) -> Option<OcppOfflineTx> {
+        offline.attempts = 0;
+        offline.retry = 0;
+        let transaction = offline.pending.pop_front()?;
+        if !transaction.start {
+            offline.tids.remove(&transaction.tid);
+            return Some(transaction);
+        }
+        let count = offline.pending.len();
+        offline.pending.retain(|entry| entry.tid != transaction.tid);
+        if offline.pending.len() == count {
+            offline.dropped.insert(transaction.tid);
+        }
+        Some(transaction)
+    }
+
+    // true once for a transaction whose StartTransaction was dropped, its stop is not sent to csms
+    pub fn offline_dropped(&self, tid: i32) -> Result<bool, AfbError> {
+        Ok(self.get_offline()?.dropped.remove(&tid))
+    }
+
+    // called every second, true when a message waiting for retry should be replayed
+    pub fn offline_tick(&self) -> Result<bool, AfbError> {
+        let mut offline = self.get_offline()?;
+        if offline.retry == 0 {
+            return Ok(false);
+        }
+        offline.retry -= 1;
+        Ok(offline.retry == 0)
+    }
+
+    fn reserve(connector: &OcppConnector, request: &ReservationSession) -> v106::ReservationStatus {
+        if !connector.availability.lock().unwrap().operative {
+            return v106::ReservationStatus::Unavailable;
+        }
+        let mut data_set = connector.data_set.lock().unwrap();
+        match &data_set.reservation {
             Some(_value) => v106::ReservationStatus::Occupied,
             None => {
-                self.event.push(OcppMsg::Reservation(request.clone()));
-                data_set.reservation = Some(request);
+                connector.event.push(OcppMsg::Reservation(request.clone()));
+                data_set.reservation = Some(request.clone());
                 v106::ReservationStatus::Accepted
             }
+        }
+    }
+
+    // connector 0 reserves every free connector, Occupied when none is free
+    pub fn reserv_now(
+        &self,
+        cid: u32,
+        request: ReservationSession,
+    ) -> Result<v106::ReservationStatus, AfbError> {
+        let targets = match self.get_targets(cid) {
+            Ok(value) => value,
+            Err(_) => return Ok(v106::ReservationStatus::Rejected),
         };
+
+        let mut response = v106::ReservationStatus::Occupied;
+        for cid in targets {
+            match (Self::reserve(self.get_connector(cid)?, &request), &response) {
+                (_, v106::ReservationStatus::Accepted) => {}
+                (status, _) => response = status,
+            }
+        }
         Ok(response)
     }
 
+    // reservation id is unique for the station, cancel it on every connector (connector 0 fan out)
     pub fn reserv_cancel(&self, rid: i32) -> Result<v106::CancelReservationStatus, AfbError> {
-        let mut data_set = self.get_state()?;
-
-        let response = match &data_set.reservation {
-            None => v106::CancelReservationStatus::Rejected,
-            Some(resa) => {
-                if rid != resa.id {
-                    v106::CancelReservationStatus::Rejected
-                } else {
-                    let mut cancel = resa.clone();
-                    cancel.status = ReservationStatus::Cancel;
-                    self.event.push(OcppMsg::Reservation(cancel));
-                    data_set.reservation = None;
-                    v106::CancelReservationStatus::Accepted
-                }
-            }
-        };
+        let mut response = v106::CancelReservationStatus::Rejected;
+        for connector in &self.connectors {
+            let mut data_set = connector.data_set.lock().unwrap();
+            let mut cancel = match &data_set.reservation {
+                Some(resa) if resa.id == rid => resa.clone(),
+                _ => continue,
+            };
+            cancel.status = ReservationStatus::Cancel;
+            connector.event.push(OcppMsg::Reservation(cancel));
+            data_set.reservation = None;
+            response = v106::CancelReservationStatus::Accepted;
+        }
         Ok(response)
     }
 
-    pub fn set_limit(&self, limit: PowerLimit) -> Result<v106::ChargingProfileStatus, AfbError> {
-        let data_set = self.get_state()?;
+    pub fn set_limit(&self, cid: u32, limit: PowerLimit) -> Result<v106::ChargingProfileStatus, AfbError> {
+        let connector = match self.get_connector(cid) {
+            Ok(value) => value,
+            Err(_) => return Ok(v106::ChargingProfileStatus::Rejected),
+        };
+        let data_set = connector.data_set.lock().unwrap();
 
         let response = if limit.tid != data_set.tid {
             v106::ChargingProfileStatus::Rejected
         } else {
-            self.event.push(OcppMsg::PowerLimit(limit));
+            connector.event.push(OcppMsg::PowerLimit(limit));
             v106::ChargingProfileStatus::Accepted
         };
         Ok(response)
     }
 
 
-    //Draft RMU: send remote stop request from OCPP to ChargingMgr
-    // ?? RemoteStopTransaction ou remoteStopTransaction
-    pub fn remote_stop_transaction(&self, remote_stop_transaction_id: i32) -> Result<(), AfbError> {
-        let mut data_set = self.get_state()?;
-
-        if data_set.tid != remote_stop_transaction_id {
-            return afb_error! ("ocpp-remote-stop-mgr", "invalid transaction id expect:{} get:{}", data_set.tid, remote_stop_transaction_id)
-        }
-
-	    self.event.push(OcppMsg::Transaction(false, remote_stop_transaction_id as u32));
-        data_set.tid=0;
-        Ok(())
+    // RemoteStopTransaction, ask chmgr to stop power, transaction remains active until StopTransaction is sent
+    // return connector running the transaction, None when csms request should be rejected
+    pub fn remote_stop_transaction(&self, tid: i32) -> Result<Option<u32>, AfbError> {
+        let cid = match self.find_tid(tid) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let connector = self.get_connector(cid)?;
+        connector.remote_stop.store(tid, Ordering::Relaxed);
+        connector.event.push(OcppMsg::Transaction(false, tid as u32));
+        Ok(Some(cid))
     }
 
-    //pub fn authorized(&self, authorized: bool) -> Result<(), AfbError> {
-    //    let mut data_set = self.get_state()?;
-    //    self.event.push(OcppMsg::Authorized(authorized));
-    //    data_set.authorized = authorized;
-    //    Ok(())
-    //}
-
+    // true while csms remote stop waits for chmgr
+    pub fn remote_stop_pending(&self, cid: u32, tid: i32) -> Result<bool, AfbError> {
+        let connector = self.get_connector(cid)?;
+        Ok(connector.remote_stop.load(Ordering::Relaxed) == tid && connector.get_tid() == tid)
+    }
 
+    // StopTransaction about to be sent, consumes pending remote stop
+    pub fn stop_reason(&self, cid: u32) -> Result<OcppStopReason, AfbError> {
+        let connector = self.get_connector(cid)?;
+        let tid = connector.get_tid();
+        if tid != 0 && connector.remote_stop.swap(0, Ordering::Relaxed) == tid {
+            Ok(OcppStopReason::Remote)
+        } else {
+            Ok(OcppStopReason::Local)
+        }
+    }
 }
@@ -0,0 +1,791 @@
+/*
+ * Copyright (C) 2015-2022 IoT.bzh Company
+ * Author: Fulup Ar Foll <fulup@iot.bzh>
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Reference:
+ *   OCPP-J-1.6 §4 RPC framework
+ *   OCPP-2.0.1 part4 §4 RPC framework
+ */
+
+use crate::prelude::*;
+#[cfg(feature = "afbv4")]
+use afbv4::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use strum_macros::Display;
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+
+// OCPP-J message type ids
+pub const OCPP_CALL: u64 = 2;
+pub const OCPP_CALL_RESULT: u64 = 3;
+pub const OCPP_CALL_ERROR: u64 = 4;
+
+// messageId is a string limited to 36 chars (OCPP-J §4.1.3)
+const OCPP_MSGID_MAX: usize = 36;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Display)]
+pub enum OcppVersion {
+    #[serde(rename = "1.6")]
+    V106,
+    #[serde(rename = "2.0.1")]
+    V201,
+}
+
+impl OcppVersion {
+    // websocket subprotocol (OCPP-J §3.1.2)
+    pub fn subprotocol(&self) -> &'static str {
+        match self {
+            OcppVersion::V106 => "ocpp1.6",
+            OcppVersion::V201 => "ocpp2.0.1",
+        }
+    }
+}
+
+impl FromStr for OcppVersion {
+    type Err = AfbError;
+
+    fn from_str(input: &str) -> Result<OcppVersion, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "1.6" | "16" | "ocpp1.6" | "ocpp-16" => Ok(OcppVersion::V106),
+            "2.0.1" | "201" | "ocpp2.0.1" | "ocpp-201" => Ok(OcppVersion::V201),
+            _ => afb_error!("ocpp-version-parse", "unsupported ocpp version:{}", input),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum OcppDirection {
+    Request,
+    Response,
+}
+
+// every OCPP payload struct knows its action name and direction, decoding never
+// guesses from json shape (empty requests used to match any untagged variant)
+pub trait OcppAction: serde::Serialize + serde::de::DeserializeOwned {
+    const ACTION: &'static str;
+    const DIRECTION: OcppDirection;
+}
+
+// decode a raw json payload into the action/direction specific structure
+pub fn ocpp_decode<T: OcppAction>(payload: Value) -> Result<T, AfbError> {
+    let error = match serde_json::from_value::<T>(payload) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    // serde data errors are only distinguished by their message
+    let info = format!("action:{} {} {}", T::ACTION, T::DIRECTION, error);
+    let text = error.to_string();
+    if !error.is_data() {
+        afb_error!("ocpp-format-violation", "{}", info)
+    } else if text.starts_with("missing field") {
+        afb_error!("ocpp-occurrence-constraint", "{}", info)
+    } else if text.starts_with("invalid type") {
+        afb_error!("ocpp-type-constraint", "{}", info)
+    } else if text.starts_with("unknown field") {
+        afb_error!("ocpp-format-violation", "{}", info)
+    } else {
+        afb_error!("ocpp-property-constraint", "{}", info)
+    }
+}
+
+// retrieve afb request/response argument as a raw json payload
+#[cfg(feature = "afbv4")]
+pub fn args_value(args: &AfbRqtData, index: usize) -> Result<Value, AfbError> {
+    let jsonc = args.get::<JsoncObj>(index)?;
+    match serde_json::from_str::<Value>(&jsonc.to_string()) {
+        Ok(value) => Ok(value),
+        Err(error) => afb_error!("ocpp-format-violation", "invalid json:{}", error),
+    }
+}
+
+// uid of the error returned when csms answered a CALL with a CALLERROR
+pub const OCPP_CSMS_CALLERROR: &str = "ocpp-csms-callerror";
+
+// OCPP-SND response payload, csms CALLERROR fails with OCPP_CSMS_CALLERROR uid. Other errors mean
+// csms did not answer (transport down, timeout)
+#[cfg(feature = "afbv4")]
+pub fn args_response(args: &AfbRqtData, index: usize) -> Result<Value, AfbError> {
+    let payload = args_value(args, index)?;
+    match OcppCallError::from_value(&payload) {
+        Some(callerror) => afb_error!(
+            OCPP_CSMS_CALLERROR,
+            "code:{} {}",
+            callerror.code,
+            callerror.description
+        ),
+        None => Ok(payload),
+    }
+}
+
+// csms answered with a CALLERROR, retrying the same message will not help
+pub fn is_callerror(error: &AfbError) -> bool {
+    error.get_uid() == OCPP_CSMS_CALLERROR
+}
+
+// decode afb request/response argument through its json representation
+#[cfg(feature = "afbv4")]
+pub fn args_decode<T: OcppAction>(args: &AfbRqtData, index: usize) -> Result<T, AfbError> {
+    ocpp_decode::<T>(args_value(args, index)?)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Display)]
+pub enum OcppCallErrorCode {
+    NotImplemented,
+    NotSupported,
+    InternalError,
+    ProtocolError,
+    SecurityError,
+    // OCPP-1.6 spelling, 2.0.1 uses FormatViolation
+    FormationViolation,
+    FormatViolation,
+    PropertyConstraintViolation,
+    // OCPP-1.6 spelling, 2.0.1 uses OccurrenceConstraintViolation
+    OccurenceConstraintViolation,
+    OccurrenceConstraintViolation,
+    TypeConstraintViolation,
+    MessageTypeNotSupported,
+    RpcFrameworkError,
+    GenericError,
+}
+
+impl OcppCallErrorCode {
+    // select the error code spelling expected by the protocol version
+    pub fn for_version(self, version: OcppVersion) -> Self {
+        match (self, version) {
+            (OcppCallErrorCode::FormatViolation, OcppVersion::V106) => {
+                OcppCallErrorCode::FormationViolation
+            }
+            (OcppCallErrorCode::FormationViolation, OcppVersion::V201) => {
+                OcppCallErrorCode::FormatViolation
+            }
+            (OcppCallErrorCode::OccurrenceConstraintViolation, OcppVersion::V106) => {
+                OcppCallErrorCode::OccurenceConstraintViolation
+            }
+            (OcppCallErrorCode::OccurenceConstraintViolation, OcppVersion::V201) => {
+                OcppCallErrorCode::OccurrenceConstraintViolation
+            }
+            (code, _) => code,
+        }
+    }
+
+    // map AfbError uid produced by ocpp-lib/binding into an OCPP error code
+    pub fn from_uid(uid: &str) -> Self {
+        match uid {
+            "ocpp-not-implemented" => OcppCallErrorCode::NotImplemented,
+            "ocpp-not-supported" => OcppCallErrorCode::NotSupported,
+            "ocpp-format-violation" => OcppCallErrorCode::FormatViolation,
+            "ocpp-type-constraint" => OcppCallErrorCode::TypeConstraintViolation,
+            "ocpp-property-constraint" => OcppCallErrorCode::PropertyConstraintViolation,
+            "ocpp-occurrence-constraint" => OcppCallErrorCode::OccurrenceConstraintViolation,
+            "ocpp-security-error" => OcppCallErrorCode::SecurityError,
+            _ => OcppCallErrorCode::InternalError,
+        }
+    }
+}
+
+// CALLERROR content returned to csms when a backend request fails
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcppCallError {
+    pub code: OcppCallErrorCode,
+    pub description: String,
+    pub details: Value,
+}
+
+impl OcppCallError {
+    pub fn new(code: OcppCallErrorCode, description: &str) -> Self {
+        OcppCallError {
+            code,
+            description: description.to_string(),
+            details: json!({}),
+        }
+    }
+
+    pub fn from_error(error: &AfbError, version: OcppVersion) -> Self {
+        let code = OcppCallErrorCode::from_uid(error.get_uid().as_str()).for_version(version);
+        OcppCallError {
+            code,
+            description: error.get_info(),
+            details: json!({"uid": error.get_uid()}),
+        }
+    }
+
+    pub fn to_frame(&self, id: &str) -> OcppFrame {
+        OcppFrame::CallError {
+            id: id.to_string(),
+            code: self.code.clone(),
+            description: self.description.clone(),
+            details: self.details.clone(),
+        }
+    }
+
+    // CALLERROR received from csms, forwarded by OCPP-SND transport with to_value() layout
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let code = value.get("errorCode")?;
+        Some(OcppCallError {
+            code: serde_json::from_value(code.clone()).unwrap_or(OcppCallErrorCode::GenericError),
+            description: value
+                .get("errorDescription")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            details: value.get("errorDetails").cloned().unwrap_or(json!({})),
+        })
+    }
+
+    // reply content expected by OCPP-REC transport to emit a CALLERROR
+    pub fn to_value(&self) -> Value {
+        json!({
+            "errorCode": self.code,
+            "errorDescription": self.description,
+            "errorDetails": self.details,
+        })
+    }
+}
+
+// OCPP-J envelopes: CALL [2,id,action,payload] CALLRESULT [3,id,payload] CALLERROR [4,id,code,desc,details]
+#[derive(Debug, Clone, PartialEq)]
+pub enum OcppFrame {
+    Call {
+        id: String,
+        action: String,
+        payload: Value,
+    },
+    CallResult {
+        id: String,
+        payload: Value,
+    },
+    CallError {
+        id: String,
+        code: OcppCallErrorCode,
+        description: String,
+        details: Value,
+    },
+}
+
+impl OcppFrame {
+    pub fn result(id: &str, payload: Value) -> Self {
+        OcppFrame::CallResult {
+            id: id.to_string(),
+            payload,
+        }
+    }
+
+    pub fn error(id: &str, code: OcppCallErrorCode, description: &str) -> Self {
+        OcppFrame::CallError {
+            id: id.to_string(),
+            code,
+            description: description.to_string(),
+            details: json!({}),
+        }
+    }
+
+    pub fn get_id(&self) -> &str {
+        match self {
+            OcppFrame::Call { id, .. } => id,
+            OcppFrame::CallResult { id, .. } => id,
+            OcppFrame::CallError { id, .. } => id,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            OcppFrame::Call {
+                id,
+                action,
+                payload,
+            } => json!([OCPP_CALL, id, action, payload]),
+            OcppFrame::CallResult { id, payload } => json!([OCPP_CALL_RESULT, id, payload]),
+            OcppFrame::CallError {
+                id,
+                code,
+                description,
+                details,
+            } => json!([OCPP_CALL_ERROR, id, code, description, details]),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        self.to_value().to_string()
+    }
+
+    pub fn decode(text: &str) -> Result<Self, AfbError> {
+        let value: Value = match serde_json::from_str(text) {
+            Ok(value) => value,
+            Err(error) => return afb_error!("ocpp-frame-decode", "invalid json:{}", error),
+        };
+        Self::from_value(value)
+    }
+
+    pub fn from_value(value: Value) -> Result<Self, AfbError> {
+        let mut items = match value {
+            Value::Array(items) => items,
+            _ => return afb_error!("ocpp-frame-decode", "frame is not a json array"),
+        };
+
+        let msgid = match items.get(1) {
+            Some(Value::String(value)) if value.len() <= OCPP_MSGID_MAX => value.clone(),
+            _ => return afb_error!("ocpp-frame-decode", "invalid or missing messageId"),
+        };
+
+        let frame = match (items.first().and_then(Value::as_u64), items.len()) {
+            (Some(OCPP_CALL), 4) => {
+                let payload = items.remove(3);
+                let action = match items.remove(2) {
+                    Value::String(value) => value,
+                    _ => {
+                        return afb_error!("ocpp-frame-decode", "invalid call action id:{}", msgid)
+                    }
+                };
+                OcppFrame::Call {
+                    id: msgid,
+                    action,
+                    payload,
+                }
+            }
+            (Some(OCPP_CALL_RESULT), 3) => OcppFrame::CallResult {
+                id: msgid,
+                payload: items.remove(2),
+            },
+            (Some(OCPP_CALL_ERROR), 5) => {
+                let details = items.remove(4);
+                let description = items[3].as_str().unwrap_or("").to_string();
+                // unknown error codes are mapped to GenericError instead of dropping the frame
+                let code = serde_json::from_value::<OcppCallErrorCode>(items.remove(2))
+                    .unwrap_or(OcppCallErrorCode::GenericError);
+                OcppFrame::CallError {
+                    id: msgid,
+                    code,
+                    description,
+                    details,
+                }
+            }
+            (msgtype, count) => {
+                return afb_error!(
+                    "ocpp-frame-decode",
+                    "invalid frame type:{:?} len:{} id:{}",
+                    msgtype,
+                    count,
+                    msgid
+                )
+            }
+        };
+        Ok(frame)
+    }
+}
+
+// response to a CALL once correlated with its pending request
+#[derive(Debug, Clone)]
+pub enum OcppRpcReply {
+    Result {
+        action: String,
+        payload: Value,
+    },
+    Error {
+        action: String,
+        code: OcppCallErrorCode,
+        description: String,
+        details: Value,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct OcppRpcPending {
+    pub action: String,
+    pub deadline: Instant,
+}
+
+pub struct OcppRpcState {
+    count: u64,
+    pending: HashMap<String, OcppRpcPending>,
+}
+
+// message-id generation, request/response correlation and per-call timeout
+pub struct OcppRpc {
+    prefix: String,
+    timeout: Duration,
+    data_set: Mutex<OcppRpcState>,
+}
+
+impl OcppRpc {
+    pub fn new(prefix: &str, timeout_ms: u64) -> Self {
+        // seed counter with current time to avoid reusing ids after a restart
+        let seed = match unix_time() {
+            Ok(value) => value.as_secs() << 16,
+            Err(_) => 0,
+        };
+
+        // keep room for the counter within messageId max length, cut on a char boundary (non ascii identity)
+        let mut prefix = prefix.to_string();
+        let mut end = prefix.len().min(OCPP_MSGID_MAX - 17);
+        while !prefix.is_char_boundary(end) {
+            end -= 1;
+        }
+        prefix.truncate(end);
+
+        OcppRpc {
+            prefix,
+            timeout: Duration::from_millis(timeout_ms),
+            data_set: Mutex::new(OcppRpcState {
+                count: seed,
+                pending: HashMap::new(),
+            }),
+        }
+    }
+
+    #[track_caller]
+    fn get_state(&self) -> Result<MutexGuard<'_, OcppRpcState>, AfbError> {
+        let guard = self.data_set.lock().unwrap();
+        Ok(guard)
+    }
+
+    fn new_msgid(&self, data_set: &mut OcppRpcState) -> String {
+        data_set.count = data_set.count.wrapping_add(1);
+        format!("{}-{:x}", self.prefix, data_set.count)
+    }
+
+    // build a CALL frame and register it for correlation (timeout=None use default)
+    pub fn call(
+        &self,
+        action: &str,
+        payload: Value,
+        timeout: Option<Duration>,
+    ) -> Result<OcppFrame, AfbError> {
+        let mut data_set = self.get_state()?;
+        let msgid = self.new_msgid(&mut data_set);
+        let deadline = Instant::now() + timeout.unwrap_or(self.timeout);
+
+        data_set.pending.insert(
+            msgid.clone(),
+            OcppRpcPending {
+                action: action.to_string(),
+                deadline,
+            },
+        );
+
+        Ok(OcppFrame::Call {
+            id: msgid,
+            action: action.to_string(),
+            payload,
+        })
+    }
+
+    // match a CALLRESULT/CALLERROR with its pending CALL
+    pub fn response(&self, frame: OcppFrame) -> Result<OcppRpcReply, AfbError> {
+        let mut data_set = self.get_state()?;
+        let pending = match data_set.pending.remove(frame.get_id()) {
+            Some(value) => value,
+            None => {
+                return afb_error!(
+                    "ocpp-rpc-response",
+                    "no pending call for id:{}",
+                    frame.get_id()
+                )
+            }
+        };
+
+        let reply = match frame {
+            OcppFrame::CallResult { payload, .. } => OcppRpcReply::Result {
+                action: pending.action,
+                payload,
+            },
+            OcppFrame::CallError {
+                code,
+                description,
+                details,
+                ..
+            } => OcppRpcReply::Error {
+                action: pending.action,
+                code,
+                description,
+                details,
+            },
+            OcppFrame::Call { id, .. } => {
+                return afb_error!("ocpp-rpc-response", "unexpected call frame id:{}", id)
+            }
+        };
+        Ok(reply)
+    }
+
+    // remove and return every call whose deadline is over
+    pub fn expired(&self) -> Result<Vec<(String, OcppRpcPending)>, AfbError> {
+        let mut data_set = self.get_state()?;
+        let now = Instant::now();
+        let expired: Vec<String> = data_set
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.deadline <= now)
+            .map(|(msgid, _)| msgid.clone())
+            .collect();
+
+        let mut response = Vec::new();
+        for msgid in expired {
+            if let Some(pending) = data_set.pending.remove(&msgid) {
+                response.push((msgid, pending));
+            }
+        }
+        Ok(response)
+    }
+
+    // OCPP-J only allows one outstanding CALL per direction
+    pub fn is_busy(&self) -> Result<bool, AfbError> {
+        let data_set = self.get_state()?;
+        Ok(!data_set.pending.is_empty())
+    }
+
+    pub fn cancel(&self, msgid: &str) -> Result<Option<OcppRpcPending>, AfbError> {
+        let mut data_set = self.get_state()?;
+        Ok(data_set.pending.remove(msgid))
+    }
+}
+
+// decode an incoming 1.6 CALL into its action tagged envelope
+pub fn v106_decode_call(frame: &OcppFrame) -> Result<v106::OcppRequest, AfbError> {
+    match frame {
+        OcppFrame::Call {
+            action, payload, ..
+        } => v106::OcppRequest::decode(action, payload.clone()),
+        _ => afb_error!("ocpp-rpc-decode", "not a call frame id:{}", frame.get_id()),
+    }
+}
+
+// decode a correlated 1.6 CALLRESULT into its action tagged envelope
+pub fn v106_decode_reply(reply: &OcppRpcReply) -> Result<v106::OcppResponse, AfbError> {
+    match reply {
+        OcppRpcReply::Result { action, payload } => {
+            v106::OcppResponse::decode(action, payload.clone())
+        }
+        OcppRpcReply::Error {
+            action,
+            code,
+            description,
+            ..
+        } => afb_error!(
+            "ocpp-rpc-decode",
+            "action:{} error:{} {}",
+            action,
+            code,
+            description
+        ),
+    }
+}
+
+// decode an incoming 2.0.1 CALL into its typed payload
+pub fn v201_decode_call(frame: &OcppFrame) -> Result<v201::OcppPayload, AfbError> {
+    let (action, payload) = match frame {
+        OcppFrame::Call {
+            action, payload, ..
+        } => (action, payload),
+        _ => return afb_error!("ocpp-rpc-decode", "not a call frame id:{}", frame.get_id()),
+    };
+
+    let action = match action.parse::<v201::OcppActionEnum>() {
+        Ok(value) => value,
+        Err(_) => return afb_error!("ocpp-rpc-decode", "unknown action:{}", action),
+    };
+
+    match v201::OcppPayload::from_request(&action, payload.clone()) {
+        Ok(value) => Ok(value),
+        Err(error) => afb_error!(
+            "ocpp-rpc-decode",
+            "action:{} invalid payload:{}",
+            action,
+            error
+        ),
+    }
+}
+
+// decode a correlated 2.0.1 CALLRESULT into its typed payload
+pub fn v201_decode_reply(reply: &OcppRpcReply) -> Result<v201::OcppPayload, AfbError> {
+    let (action, payload) = match reply {
+        OcppRpcReply::Result { action, payload } => (action, payload),
+        OcppRpcReply::Error {
+            action,
+            code,
+            description,
+            ..
+        } => {
+            return afb_error!(
+                "ocpp-rpc-decode",
+                "action:{} error:{} {}",
+                action,
+                code,
+                description
+            )
+        }
+    };
+
+    let action = match action.parse::<v201::OcppActionEnum>() {
+        Ok(value) => value,
+        Err(_) => return afb_error!("ocpp-rpc-decode", "unknown action:{}", action),
+    };
+
+    match v201::OcppPayload::from_response(&action, payload.clone()) {
+        Ok(value) => Ok(value),
+        Err(error) => afb_error!(
+            "ocpp-rpc-decode",
+            "action:{} invalid payload:{}",
+            action,
+            error
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn frame_call_roundtrip() {
+        let text = r#"[2,"id-1","Heartbeat",{}]"#;
+        let frame = OcppFrame::decode(text).unwrap();
+        assert_eq!(
+            frame,
+            OcppFrame::Call {
+                id: "id-1".to_string(),
+                action: "Heartbeat".to_string(),
+                payload: json!({}),
+            }
+        );
+        assert_eq!(OcppFrame::decode(&frame.encode()).unwrap(), frame);
+    }
+
+    #[test]
+    fn frame_result_roundtrip() {
+        let frame = OcppFrame::result("id-2", json!({"currentTime": "2023-01-01T00:00:00Z"}));
+        assert_eq!(
+            frame.to_value(),
+            json!([3, "id-2", {"currentTime": "2023-01-01T00:00:00Z"}])
+        );
+        assert_eq!(OcppFrame::decode(&frame.encode()).unwrap(), frame);
+    }
+
+    #[test]
+    fn frame_error_roundtrip() {
+        let frame = OcppFrame::error("id-3", OcppCallErrorCode::NotImplemented, "unknown action");
+        assert_eq!(
+            frame.to_value(),
+            json!([4, "id-3", "NotImplemented", "unknown action", {}])
+        );
+        assert_eq!(OcppFrame::decode(&frame.encode()).unwrap(), frame);
+
+        // unknown error codes are kept as GenericError
+        let frame = OcppFrame::decode(r#"[4,"id-4","Whatever","oops",{}]"#).unwrap();
+        match frame {
+            OcppFrame::CallError { code, .. } => assert_eq!(code, OcppCallErrorCode::GenericError),
+            _ => panic!("not a call error"),
+        }
+    }
+
+    #[test]
+    fn callerror_from_value() {
+        let callerror = OcppCallError::new(OcppCallErrorCode::FormationViolation, "bad payload");
+        assert_eq!(OcppCallError::from_value(&callerror.to_value()), Some(callerror));
+
+        let callerror = OcppCallError::from_value(&json!({"errorCode": "Whatever"})).unwrap();
+        assert_eq!(callerror.code, OcppCallErrorCode::GenericError);
+        assert_eq!(callerror.description, "");
+
+        // regular csms responses are not errors
+        assert_eq!(OcppCallError::from_value(&json!({"transactionId": 1})), None);
+        let error: Result<(), AfbError> = afb_error!(OCPP_CSMS_CALLERROR, "code:{}", "GenericError");
+        assert!(is_callerror(&error.unwrap_err()));
+        let error: Result<(), AfbError> = afb_error!("ocpp-format-violation", "invalid json");
+        assert!(!is_callerror(&error.unwrap_err()));
+    }
+
+    #[test]
+    fn frame_decode_invalid() {
+        assert!(OcppFrame::decode("{}").is_err());
+        assert!(OcppFrame::decode(r#"[2,"id",{}]"#).is_err());
+        assert!(OcppFrame::decode(r#"[3,12,{}]"#).is_err());
+        assert!(OcppFrame::decode(r#"[5,"id",{}]"#).is_err());
+        let msgid = "x".repeat(OCPP_MSGID_MAX + 1);
+        assert!(OcppFrame::decode(&format!(r#"[3,"{}",{{}}]"#, msgid)).is_err());
+    }
+
+    #[test]
+    fn rpc_msgid_unique() {
+        let rpc = OcppRpc::new("station", 1000);
+        let mut ids = HashSet::new();
+        for _ in 0..100 {
+            let frame = rpc.call("Heartbeat", json!({}), None).unwrap();
+            assert!(frame.get_id().len() <= OCPP_MSGID_MAX);
+            assert!(ids.insert(frame.get_id().to_string()));
+        }
+    }
+
+    #[test]
+    fn rpc_msgid_non_ascii_prefix() {
+        let rpc = OcppRpc::new(&"é".repeat(OCPP_MSGID_MAX), 1000);
+        let frame = rpc.call("Heartbeat", json!({}), None).unwrap();
+        assert!(frame.get_id().len() <= OCPP_MSGID_MAX);
+    }
+
+    #[test]
+    fn rpc_correlation() {
+        let rpc = OcppRpc::new("station", 1000);
+        let frame = rpc.call("Heartbeat", json!({}), None).unwrap();
+        assert!(rpc.is_busy().unwrap());
+
+        let reply = rpc.response(OcppFrame::result(frame.get_id(), json!({}))).unwrap();
+        match reply {
+            OcppRpcReply::Result { action, .. } => assert_eq!(action, "Heartbeat"),
+            _ => panic!("not a call result"),
+        }
+        assert!(!rpc.is_busy().unwrap());
+
+        // response is only accepted once
+        assert!(rpc.response(OcppFrame::result(frame.get_id(), json!({}))).is_err());
+        assert!(rpc.response(OcppFrame::result("unknown", json!({}))).is_err());
+    }
+
+    #[test]
+    fn rpc_correlation_error() {
+        let rpc = OcppRpc::new("station", 1000);
+        let frame = rpc.call("Authorize", json!({"idTag": "tag"}), None).unwrap();
+        let error = OcppFrame::error(frame.get_id(), OcppCallErrorCode::NotSupported, "no");
+        match rpc.response(error).unwrap() {
+            OcppRpcReply::Error { action, code, .. } => {
+                assert_eq!(action, "Authorize");
+                assert_eq!(code, OcppCallErrorCode::NotSupported);
+            }
+            _ => panic!("not a call error"),
+        }
+    }
+
+    #[test]
+    fn rpc_expired() {
+        let rpc = OcppRpc::new("station", 60000);
+        let expired = rpc.call("Heartbeat", json!({}), Some(Duration::ZERO)).unwrap();
+        let pending = rpc.call("Heartbeat", json!({}), None).unwrap();
+
+        let list = rpc.expired().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].0, expired.get_id());
+        assert!(rpc.expired().unwrap().is_empty());
+        assert!(rpc.cancel(pending.get_id()).unwrap().is_some());
+    }
+
+    #[test]
+    fn rpc_cancel() {
+        let rpc = OcppRpc::new("station", 1000);
+        let frame = rpc.call("Heartbeat", json!({}), None).unwrap();
+        let pending = rpc.cancel(frame.get_id()).unwrap().unwrap();
+        assert_eq!(pending.action, "Heartbeat");
+        assert!(rpc.cancel(frame.get_id()).unwrap().is_none());
+        assert!(rpc.response(OcppFrame::result(frame.get_id(), json!({}))).is_err());
+        assert!(!rpc.is_busy().unwrap());
+    }
+}
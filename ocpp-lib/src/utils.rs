@@ -9,12 +9,24 @@
  *   http://www.apache.org/licenses/LICENSE-2.0
  *
  */
+#[cfg(feature = "afbv4")]
 use afbv4::prelude::*;
-use chrono::{DateTime, Utc}; // require feature=now
-use std::time::{Duration, SystemTime};
+#[cfg(not(feature = "afbv4"))]
+use typesv4::compat::*;
+use chrono::{DateTime, SecondsFormat, Utc}; // require feature=now
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::time::Duration;
+
+// offset (ms) between csms and local clock, boards without RTC boot far away from real time
+static CLOCK_OFFSET_MS: AtomicI64 = AtomicI64::new(0);
+static CLOCK_SYNCED: AtomicBool = AtomicBool::new(false);
+
+// network latency jitter ignored once synchronised
+const CLOCK_JITTER_MS: i64 = 1000;
 
 pub fn now_to_duration(date: DateTime<Utc>) -> Result<std::time::Duration, AfbError> {
-    let now = Utc::now();
+    let now = get_utc();
     let offset = date - now;
     match offset.to_std() {
         Ok(value) => Ok(value),
@@ -24,14 +36,58 @@ pub fn now_to_duration(date: DateTime<Utc>) -> Result<std::time::Duration, AfbEr
     }
 }
 
+// local time corrected with csms clock offset
 pub fn get_utc() -> DateTime<Utc> {
-    Utc::now()
+    Utc::now() + chrono::Duration::milliseconds(CLOCK_OFFSET_MS.load(Ordering::Relaxed))
 }
 
 pub fn unix_time() -> Result<Duration, AfbError> {
-    let unix_time = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-        Ok(value) => value,
-        Err(_) => return afb_error!("energy-check-state", "system time before UNIX EPOCH!"),
-    };
-    Ok(unix_time)
+    match u64::try_from(get_utc().timestamp_millis()) {
+        Ok(value) => Ok(Duration::from_millis(value)),
+        Err(_) => afb_error!("energy-check-state", "system time before UNIX EPOCH!"),
+    }
+}
+
+// adopt csms currentTime (BootNotification, Heartbeat), return true when offset changed
+pub fn clock_sync(csms_time: DateTime<Utc>) -> bool {
+    let offset = (csms_time - Utc::now()).num_milliseconds();
+    let previous = CLOCK_OFFSET_MS.load(Ordering::Relaxed);
+    if CLOCK_SYNCED.swap(true, Ordering::Relaxed) && (offset - previous).abs() < CLOCK_JITTER_MS {
+        return false;
+    }
+    CLOCK_OFFSET_MS.store(offset, Ordering::Relaxed);
+    offset != previous
+}
+
+pub fn clock_synced() -> bool {
+    CLOCK_SYNCED.load(Ordering::Relaxed)
+}
+
+pub fn clock_offset() -> i64 {
+    CLOCK_OFFSET_MS.load(Ordering::Relaxed)
+}
+
+// move every "timestamp" field of a json message by delta ms
+pub fn shift_timestamps(value: &mut Value, delta: i64) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                match item {
+                    Value::String(text) if key == "timestamp" => {
+                        if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+                            let date = date.with_timezone(&Utc) + chrono::Duration::milliseconds(delta);
+                            *text = date.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+                        }
+                    }
+                    _ => shift_timestamps(item, delta),
+                }
+            }
+        }
+        Value::Array(list) => {
+            for item in list.iter_mut() {
+                shift_timestamps(item, delta);
+            }
+        }
+        _ => {}
+    }
 }